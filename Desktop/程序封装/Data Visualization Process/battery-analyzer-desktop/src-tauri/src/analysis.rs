@@ -0,0 +1,89 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+// Python脚本在stderr中输出结构化错误的前缀，格式为
+// ANALYSIS_ERROR {"file": "A1.xlsx", "row": 12, "message": "放电比容量为空"}
+const ANALYSIS_ERROR_PREFIX: &str = "ANALYSIS_ERROR";
+
+// Python脚本报告的数据问题，file/row 无法确定时为 None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisError {
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub row: Option<u64>,
+    pub message: String,
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.row) {
+            (Some(file), Some(row)) => {
+                write!(f, "{}", tr!("{} 第 {} 行: {}", "{} row {}: {}", file, row, self.message))
+            }
+            (Some(file), None) => write!(f, "{}: {}", file, self.message),
+            (None, Some(row)) => write!(f, "{}", tr!("第 {} 行: {}", "Row {}: {}", row, self.message)),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// 从stderr中提取结构化错误，格式不正确的行按普通输出处理
+pub fn parse_errors(stderr: &str) -> Vec<AnalysisError> {
+    stderr
+        .lines()
+        .filter_map(|line| {
+            let json = line.trim().strip_prefix(ANALYSIS_ERROR_PREFIX)?;
+            if !json.starts_with(char::is_whitespace) {
+                return None;
+            }
+            serde_json::from_str(json.trim()).ok()
+        })
+        .collect()
+}
+
+// stderr行的级别，随 battery://error 事件发送
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StderrLevel {
+    Error,
+    Warning,
+    Info,
+}
+
+// 逐行判断stderr输出的级别。Python的异常回溯跨多行，
+// 从 "Traceback" 开始到最后一行（不缩进的异常说明）都视为错误
+#[derive(Debug, Default)]
+pub struct StderrClassifier {
+    in_traceback: bool,
+}
+
+impl StderrClassifier {
+    pub fn classify(&mut self, line: &str) -> StderrLevel {
+        if line.starts_with("Traceback (most recent call last)") {
+            self.in_traceback = true;
+            return StderrLevel::Error;
+        }
+        if self.in_traceback {
+            if !line.starts_with(char::is_whitespace) {
+                self.in_traceback = false;
+            }
+            return StderrLevel::Error;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with(ANALYSIS_ERROR_PREFIX) || trimmed.starts_with("ERROR") {
+            StderrLevel::Error
+        } else if trimmed.starts_with("WARNING") || line.contains("Warning:") {
+            // 包括 warnings 模块输出的 UserWarning、FutureWarning 等
+            StderrLevel::Warning
+        } else if line.contains("Error:") {
+            StderrLevel::Error
+        } else {
+            StderrLevel::Info
+        }
+    }
+}
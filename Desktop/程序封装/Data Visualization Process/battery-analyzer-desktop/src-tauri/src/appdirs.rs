@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppError;
+use crate::opener;
+
+// 应用使用的目录
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum AppDir {
+    // 预设和最近使用的文件夹
+    Config,
+    Data,
+    // 运行日志（runs.log）
+    Log,
+    Cache,
+}
+
+// 各应用目录的完整路径（目录可能尚未创建）
+#[derive(Debug, Serialize)]
+pub struct AppDirs {
+    pub config: String,
+    pub data: String,
+    pub log: String,
+    pub cache: String,
+}
+
+fn resolve(app: &AppHandle, which: AppDir) -> Result<PathBuf, AppError> {
+    let path = app.path();
+    match which {
+        AppDir::Config => path.app_config_dir(),
+        AppDir::Data => path.app_data_dir(),
+        AppDir::Log => path.app_log_dir(),
+        AppDir::Cache => path.app_cache_dir(),
+    }
+    .map_err(|e| AppError::AppDirUnavailable(e.to_string()))
+}
+
+pub fn app_dirs(app: &AppHandle) -> Result<AppDirs, AppError> {
+    let dir = |which| resolve(app, which).map(|path| path.to_string_lossy().to_string());
+    Ok(AppDirs {
+        config: dir(AppDir::Config)?,
+        data: dir(AppDir::Data)?,
+        log: dir(AppDir::Log)?,
+        cache: dir(AppDir::Cache)?,
+    })
+}
+
+// 在文件管理器中打开应用目录，目录尚未创建时先创建
+pub fn open(app: &AppHandle, which: AppDir) -> Result<(), AppError> {
+    let dir = resolve(app, which)?;
+    fs::create_dir_all(&dir).map_err(|e| AppError::io("创建应用目录失败", e))?;
+    opener::reveal(&dir.to_string_lossy())
+}
@@ -0,0 +1,173 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::config::{ProcessConfig, QueueOrder};
+use crate::error::AppError;
+use crate::{estimate, output};
+use crate::runner::{self, ProcessOutcome, ProcessResult};
+
+// 批量处理中单个文件夹的状态事件名
+const FOLDER_STATUS_EVENT: &str = "battery://folder-status";
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum FolderStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+// 文件夹状态事件负载
+#[derive(Debug, Clone, Serialize)]
+struct FolderStatusEvent<'a> {
+    index: usize,
+    total: usize,
+    input_folder: &'a str,
+    status: FolderStatus,
+}
+
+// 依次（或按 max_concurrent 并发）处理多个输入文件夹，结果顺序与输入顺序一致。
+// 多个文件夹共用绝对路径的 output_folder 时，每个文件夹的结果写入以其名称命名的子目录
+// （同名文件夹加序号区分，见 output_subdirs）；为空或相对路径时各自相对于所在的输入文件夹，不会互相覆盖
+pub fn run_batch(app: &AppHandle, config: ProcessConfig) -> Vec<ProcessOutcome> {
+    let folders = if config.input_folders.is_empty() {
+        vec![config.input_folder.clone()]
+    } else {
+        config.input_folders.clone()
+    };
+    let total = folders.len();
+    let workers = config.max_concurrent.unwrap_or(1).clamp(1, total.max(1));
+
+    let emit = |index: usize, status: FolderStatus| {
+        if config.headless {
+            return;
+        }
+        let _ = app.emit(
+            FOLDER_STATUS_EVENT,
+            FolderStatusEvent {
+                index,
+                total,
+                input_folder: &folders[index],
+                status,
+            },
+        );
+    };
+    for index in 0..total {
+        emit(index, FolderStatus::Queued);
+    }
+
+    // 按 queue_order 决定处理顺序，结果仍按输入顺序返回
+    let subdirs = output_subdirs(&folders);
+    let folder_configs: Vec<ProcessConfig> = folders
+        .iter()
+        .zip(&subdirs)
+        .map(|(folder, subdir)| {
+            config_for_folder(&config, folder, (total > 1).then_some(subdir.as_str()))
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..total).collect();
+    if config.queue_order != QueueOrder::AsIs {
+        let costs: Vec<u64> = folder_configs.iter().map(estimate::job_cost).collect();
+        config.queue_order.sort_by_cost(&mut order, |&index| costs[index]);
+    }
+
+    // 共用绝对路径的输出文件夹时所有结果写入同一磁盘，启动前按总量检查一次；
+    // 其他情况下各文件夹的运行分别检查
+    if total > 1 && Path::new(&config.output_folder).is_absolute() && !config.output_to_stdout {
+        let input_bytes = folder_configs.iter().map(estimate::job_cost).sum();
+        let required = config.required_space(input_bytes);
+        let dir = Path::new(&config.output_folder);
+        if let Some(available) = output::space_shortfall(dir, required) {
+            return folders
+                .iter()
+                .enumerate()
+                .map(|(index, folder)| {
+                    emit(index, FolderStatus::Failed);
+                    let error = AppError::InsufficientDiskSpace {
+                        path: config.output_folder.clone(),
+                        required,
+                        available,
+                    };
+                    ProcessOutcome::Run(ProcessResult::from_error(folder.clone(), error))
+                })
+                .collect();
+        }
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<ProcessOutcome>>> = Mutex::new((0..total).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some(&index) = order.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    emit(index, FolderStatus::Running);
+                    let result = runner::execute(app, folder_configs[index].clone())
+                        .unwrap_or_else(|e| {
+                            ProcessOutcome::Run(ProcessResult::from_error(folders[index].clone(), e))
+                        });
+                    emit(
+                        index,
+                        if result.success() { FolderStatus::Done } else { FolderStatus::Failed },
+                    );
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+// subdir 为共用输出文件夹时该文件夹结果所在的子目录名，只处理一个文件夹时为 None
+fn config_for_folder(config: &ProcessConfig, folder: &str, subdir: Option<&str>) -> ProcessConfig {
+    let mut folder_config = config.clone();
+    folder_config.input_folder = folder.to_string();
+    folder_config.input_folders.clear();
+
+    if let Some(subdir) = subdir.filter(|_| Path::new(&config.output_folder).is_absolute()) {
+        folder_config.output_folder = Path::new(&config.output_folder)
+            .join(subdir)
+            .to_string_lossy()
+            .to_string();
+    }
+    folder_config
+}
+
+// 各输入文件夹的输出子目录名：默认为文件夹名；不同路径下的同名文件夹（如 /a/day1 和 /b/day1，
+// 不区分大小写）加上其在列表中的序号，如 "day1-1"、"day1-2"；没有名称的路径（如根目录）为 "folder-序号"
+fn output_subdirs(folders: &[String]) -> Vec<String> {
+    let names: Vec<String> = folders
+        .iter()
+        .map(|folder| {
+            Path::new(folder)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+        .collect();
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            if name.is_empty() {
+                return format!("folder-{}", index + 1);
+            }
+            let duplicated = names
+                .iter()
+                .filter(|other| other.to_lowercase() == name.to_lowercase())
+                .count()
+                > 1;
+            if duplicated {
+                format!("{}-{}", name, index + 1)
+            } else {
+                name.clone()
+            }
+        })
+        .collect()
+}
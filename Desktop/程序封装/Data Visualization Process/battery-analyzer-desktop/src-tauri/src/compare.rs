@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use calamine::Data;
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::excel;
+use crate::i18n::tr;
+use crate::report::{self, Table};
+use crate::scan;
+
+// 参与对比的工作表及其行键列（见 modules/data_processor.py 和 main_processor.py 的列定义）
+const COMPARED_SHEETS: &[(&str, &[&str])] = &[
+    (report::MAIN_SHEET, &["系列", "批次", "主机", "通道"]),
+    (report::STATISTICS_SHEET, &["系列", "批次"]),
+];
+// 差值不超过该值时视为相同，避免浮点误差产生大量无意义的差异
+const EPSILON: f64 = 1e-9;
+
+// 两次分析运行的对比结果
+#[derive(Debug, Serialize)]
+pub struct RunComparison {
+    // 双方使用的汇总工作簿
+    pub result_a: String,
+    pub result_b: String,
+    pub sheets: Vec<SheetComparison>,
+}
+
+// 单个工作表的对比：按键列匹配行，只在一侧出现的行单独列出
+#[derive(Debug, Serialize)]
+pub struct SheetComparison {
+    pub sheet: String,
+    pub key_columns: Vec<String>,
+    // 工作表缺失或缺少键列时的说明，此时其余字段为空
+    pub missing: Option<String>,
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+    pub changed: Vec<RowDiff>,
+    // 双方都有且数值完全相同的行数
+    pub unchanged: usize,
+}
+
+// 双方都有但数值不同的行
+#[derive(Debug, Serialize)]
+pub struct RowDiff {
+    pub key: String,
+    pub deltas: Vec<MetricDelta>,
+}
+
+// 单个指标的变化，delta 为 b - a，任一侧为空时为 None
+#[derive(Debug, Serialize)]
+pub struct MetricDelta {
+    pub column: String,
+    pub a: Option<f64>,
+    pub b: Option<f64>,
+    pub delta: Option<f64>,
+}
+
+// 对比两个结果文件夹中的汇总工作簿（见 report::result_workbook），用于调整异常检测参数后查看结果变化
+pub fn compare_results(dir_a: &str, dir_b: &str) -> Result<RunComparison, AppError> {
+    let path_a = result_workbook(dir_a)?;
+    let path_b = result_workbook(dir_b)?;
+    let result_a = path_a.to_string_lossy().to_string();
+    let result_b = path_b.to_string_lossy().to_string();
+    let mut workbook_a = excel::open_workbook(&result_a)?;
+    let mut workbook_b = excel::open_workbook(&result_b)?;
+
+    let sheets = COMPARED_SHEETS
+        .iter()
+        .map(|(sheet, keys)| {
+            let table_a = excel::read_sheet(&mut workbook_a, &result_a, Some(sheet));
+            let table_b = excel::read_sheet(&mut workbook_b, &result_b, Some(sheet));
+            match (table_a, table_b) {
+                (Ok((_, a)), Ok((_, b))) => {
+                    compare_tables(sheet, keys, &Table::from_range(&a), &Table::from_range(&b))
+                }
+                (Err(e), _) | (_, Err(e)) => SheetComparison::missing(sheet, e.to_string()),
+            }
+        })
+        .collect();
+
+    Ok(RunComparison { result_a, result_b, sheets })
+}
+
+fn result_workbook(dir: &str) -> Result<PathBuf, AppError> {
+    scan::check_directory(dir)?;
+    report::result_workbook(Path::new(dir))
+        .ok_or_else(|| AppError::ResultNotFound(dir.to_string()))
+}
+
+impl SheetComparison {
+    fn new(sheet: &str, key_columns: &[&str]) -> Self {
+        SheetComparison {
+            sheet: sheet.to_string(),
+            key_columns: key_columns.iter().map(|key| key.to_string()).collect(),
+            missing: None,
+            only_in_a: Vec::new(),
+            only_in_b: Vec::new(),
+            changed: Vec::new(),
+            unchanged: 0,
+        }
+    }
+
+    fn missing(sheet: &str, reason: String) -> Self {
+        SheetComparison { missing: Some(reason), ..SheetComparison::new(sheet, &[]) }
+    }
+}
+
+fn compare_tables(sheet: &str, keys: &[&str], a: &Table, b: &Table) -> SheetComparison {
+    // 键列取双方都有的列，旧版输出可能缺少部分列
+    let key_columns: Vec<&str> = keys
+        .iter()
+        .copied()
+        .filter(|key| a.column(key).is_some() && b.column(key).is_some())
+        .collect();
+    if key_columns.is_empty() {
+        return SheetComparison::missing(
+            sheet,
+            tr!("缺少键列: {}", "Missing key columns: {}", keys.join(", ")),
+        );
+    }
+    let key_set: HashSet<&str> = key_columns.iter().copied().collect();
+
+    // 指标列：双方都有、不是键列、且至少一侧含有数值
+    let metrics: Vec<&str> = a
+        .headers
+        .iter()
+        .map(String::as_str)
+        .filter(|header| !key_set.contains(header) && b.column(header).is_some())
+        .filter(|header| has_numbers(a, header) || has_numbers(b, header))
+        .collect();
+
+    let rows_a = keyed_rows(a, &key_columns);
+    let mut rows_b = keyed_rows(b, &key_columns);
+
+    let mut comparison = SheetComparison::new(sheet, &key_columns);
+    for (key, row_a) in rows_a {
+        let Some(row_b) = rows_b.remove(&key) else {
+            comparison.only_in_a.push(key);
+            continue;
+        };
+        let deltas: Vec<MetricDelta> = metrics
+            .iter()
+            .filter_map(|column| {
+                let value_a = number_at(a, row_a, column);
+                let value_b = number_at(b, row_b, column);
+                let delta = match (value_a, value_b) {
+                    (Some(x), Some(y)) if (y - x).abs() <= EPSILON => return None,
+                    (None, None) => return None,
+                    (Some(x), Some(y)) => Some(y - x),
+                    _ => None,
+                };
+                Some(MetricDelta { column: column.to_string(), a: value_a, b: value_b, delta })
+            })
+            .collect();
+        if deltas.is_empty() {
+            comparison.unchanged += 1;
+        } else {
+            comparison.changed.push(RowDiff { key, deltas });
+        }
+    }
+    comparison.only_in_b = rows_b.into_keys().collect();
+    comparison
+}
+
+// 按键列的值索引各行，键相同的行依次加上 (2)、(3) 等后缀
+fn keyed_rows<'a>(table: &'a Table, key_columns: &[&str]) -> BTreeMap<String, &'a [Data]> {
+    let indexes: Vec<usize> = key_columns.iter().filter_map(|key| table.column(key)).collect();
+    let mut rows = BTreeMap::new();
+    for row in &table.rows {
+        let key = indexes
+            .iter()
+            .map(|&i| row.get(i).map(excel::cell_to_string).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(" / ");
+        let mut unique = key.clone();
+        let mut n = 1;
+        while rows.contains_key(&unique) {
+            n += 1;
+            unique = format!("{} ({})", key, n);
+        }
+        rows.insert(unique, row.as_slice());
+    }
+    rows
+}
+
+fn has_numbers(table: &Table, column: &str) -> bool {
+    let Some(index) = table.column(column) else { return false };
+    table
+        .rows
+        .iter()
+        .any(|row| row.get(index).and_then(report::as_number).is_some())
+}
+
+fn number_at(table: &Table, row: &[Data], column: &str) -> Option<f64> {
+    row.get(table.column(column)?).and_then(report::as_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_xlsxwriter::Workbook;
+
+    // 写入只含主数据和统计数据的汇总工作簿，首放取 discharge
+    fn write_result(dir: &Path, discharge: f64) {
+        let mut workbook = Workbook::new();
+        let main = workbook.add_worksheet().set_name(report::MAIN_SHEET).unwrap();
+        for (col, title) in ["系列", "批次", "主机", "通道", "首放"].iter().enumerate() {
+            main.write_string(0, col as u16, *title).unwrap();
+        }
+        for (col, value) in ["A", "B1", "1", "1"].iter().enumerate() {
+            main.write_string(1, col as u16, *value).unwrap();
+        }
+        main.write_number(1, 4, discharge).unwrap();
+        let stats = workbook.add_worksheet().set_name(report::STATISTICS_SHEET).unwrap();
+        for (col, title) in ["系列", "批次", "首放平均值"].iter().enumerate() {
+            stats.write_string(0, col as u16, *title).unwrap();
+        }
+        stats.write_string(1, 0, "A").unwrap();
+        stats.write_string(1, 1, "B1").unwrap();
+        stats.write_number(1, 2, 180.0).unwrap();
+        workbook.save(dir.join("电池数据汇总表-20240101_120000.xlsx")).unwrap();
+    }
+
+    #[test]
+    fn compares_main_processor_results() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        write_result(a.path(), 180.0);
+        write_result(b.path(), 181.5);
+
+        let comparison =
+            compare_results(&a.path().to_string_lossy(), &b.path().to_string_lossy()).unwrap();
+        let main = &comparison.sheets[0];
+        assert_eq!(main.sheet, report::MAIN_SHEET);
+        assert_eq!(main.missing, None);
+        assert_eq!(main.changed.len(), 1);
+        assert_eq!(main.changed[0].deltas[0].column, "首放");
+        assert_eq!(main.changed[0].deltas[0].delta, Some(1.5));
+        let stats = &comparison.sheets[1];
+        assert_eq!(stats.missing, None);
+        assert_eq!(stats.unchanged, 1);
+    }
+
+    #[test]
+    fn rejects_folder_without_result() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        write_result(a.path(), 180.0);
+        let result = compare_results(&a.path().to_string_lossy(), &b.path().to_string_lossy());
+        assert!(matches!(result, Err(AppError::ResultNotFound(_))));
+    }
+}
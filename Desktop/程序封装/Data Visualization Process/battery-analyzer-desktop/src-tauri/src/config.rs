@@ -0,0 +1,625 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+use crate::scan::{self, FileKind};
+
+// 支持的异常检测方法
+pub const OUTLIER_METHODS: &[&str] = &["boxplot", "zscore_mad"];
+
+// 支持的参考通道选择方法（与 modules/config_parser.py 的 choices 一致）
+pub const REFERENCE_CHANNEL_METHODS: &[&str] = &["traditional", "pca", "retention_curve_mse"];
+
+// 由应用设置、不允许通过 env 覆盖的环境变量（激活venv/conda环境时使用）
+pub const RESERVED_ENV_VARS: &[&str] = &["PATH", "VIRTUAL_ENV", "CONDA_PREFIX", "CONDA_DEFAULT_ENV"];
+
+// Python端支持的日志级别
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug"];
+
+// 日志级别对应的 config_parser.py --log_level 取值
+const PYTHON_LOG_LEVELS: &[(&str, &str)] =
+    &[("error", "ERROR"), ("warn", "WARNING"), ("info", "INFO"), ("debug", "DEBUG")];
+
+// 输出文件名模板支持的占位符，由 main.py 替换为运行日期、时间、输入文件夹名和异常检测方法
+pub const OUTPUT_NAME_TOKENS: &[&str] = &["date", "time", "input_name", "method"];
+
+// 随机数种子的最大值（NumPy要求种子为32位无符号整数）
+pub const MAX_SEED: u64 = u32::MAX as u64;
+
+// Windows文件名中不允许的字符（另外不允许控制字符）
+const INVALID_FILENAME_CHARS: &[char] = &['/', '\\', '<', '>', ':', '"', '|', '?', '*'];
+
+// 配置下拉框的可选值，由前端通过 get_config_options 获取
+#[derive(Debug, Serialize)]
+pub struct ConfigOptions {
+    pub outlier_methods: Vec<String>,
+    pub reference_channel_methods: Vec<String>,
+    pub log_levels: Vec<String>,
+    pub output_name_tokens: Vec<String>,
+}
+
+impl ConfigOptions {
+    pub fn current() -> Self {
+        let to_vec = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        ConfigOptions {
+            outlier_methods: to_vec(OUTLIER_METHODS),
+            reference_channel_methods: to_vec(REFERENCE_CHANNEL_METHODS),
+            log_levels: to_vec(LOG_LEVELS),
+            output_name_tokens: to_vec(OUTPUT_NAME_TOKENS),
+        }
+    }
+}
+
+// 输出文件夹中已有文件时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum OverwritePolicy {
+    // 存在可能被覆盖的文件时拒绝运行
+    Fail,
+    // 直接覆盖（默认，与旧版行为一致）
+    #[default]
+    Overwrite,
+    // 在输出文件夹下创建带时间戳的子目录
+    Rename,
+}
+
+// 批量处理和任务队列的执行顺序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum QueueOrder {
+    // 按给定顺序（默认）
+    #[default]
+    AsIs,
+    // 输入文件总大小最小的先处理，尽快看到第一批结果
+    SmallestFirst,
+    LargestFirst,
+}
+
+impl QueueOrder {
+    // 按成本稳定排序，成本相同时保持原有顺序
+    pub fn sort_by_cost<T, K: Ord>(self, items: &mut [T], cost: impl Fn(&T) -> K) {
+        match self {
+            QueueOrder::AsIs => {}
+            QueueOrder::SmallestFirst => items.sort_by_key(cost),
+            QueueOrder::LargestFirst => items.sort_by_key(|item| std::cmp::Reverse(cost(item))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessConfig {
+    // 基础配置
+    pub input_folder: String,
+    // 为空时为输入文件夹下的 output 子目录，相对路径相对于输入文件夹
+    pub output_folder: String,
+
+    // 输出文件夹已有结果时的处理策略
+    #[serde(default)]
+    pub overwrite_policy: OverwritePolicy,
+    // 结果文件名模板（不含扩展名），如 "{input_name}_{date}"，可用占位符见 OUTPUT_NAME_TOKENS。
+    // 未设置时使用脚本默认的文件名
+    #[serde(default)]
+    pub output_name_template: Option<String>,
+
+    // 批量处理的多个输入文件夹（可选，为空时只处理 input_folder）
+    #[serde(default)]
+    pub input_folders: Vec<String>,
+    // 只处理指定的 .xlsx 数据文件（可选，为空时处理整个 input_folder）
+    #[serde(default)]
+    pub files: Vec<String>,
+    // 只处理文件名与通配符匹配的数据文件（glob语法，如 "*_cycle.xlsx"，不区分大小写），同样作用于 files
+    #[serde(default)]
+    pub pattern: Option<String>,
+    // 为 true 时，输入文件正被其他程序（如Excel）占用则直接报错，不启动Python
+    #[serde(default)]
+    pub fail_on_locked: bool,
+    // 输入文件为空或过小时：为 true 则跳过这些文件并在结果中列出，否则直接报错
+    #[serde(default)]
+    pub skip_invalid: bool,
+    // 批量处理时同时运行的最大任务数（默认1，即顺序执行）
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    // 批量处理时各文件夹、任务队列中各任务的执行顺序（按输入文件总大小）
+    #[serde(default)]
+    pub queue_order: QueueOrder,
+
+    // 异常检测配置
+    #[schemars(schema_with = "outlier_method_schema")]
+    pub outlier_method: String,
+    pub boxplot_threshold_discharge: f64,
+    pub boxplot_threshold_efficiency: f64,
+    pub zscore_threshold_discharge: f64,
+    pub zscore_threshold_efficiency: f64,
+    pub zscore_mad_constant: f64,
+
+    // 要分析的循环数据工作表（可选，未指定时使用脚本默认的 "Cycle"）
+    #[serde(default)]
+    pub cycle_sheet_name: Option<String>,
+
+    // Python解释器（可选，未指定时依次尝试 python、python3）
+    #[serde(default)]
+    pub python_executable: Option<String>,
+    // Python虚拟环境目录（可选，优先于 conda_env 和 python_executable）
+    #[serde(default)]
+    pub venv_path: Option<String>,
+    // conda环境名称或目录（可选）
+    #[serde(default)]
+    pub conda_env: Option<String>,
+
+    // 分析脚本路径（可选，高级用户覆盖默认的打包脚本）
+    #[serde(default)]
+    pub script_path: Option<String>,
+
+    // Python进程的工作目录（可选，未指定时为脚本所在目录，使脚本的相对路径资源可以找到）
+    #[serde(default)]
+    pub working_dir: Option<String>,
+
+    // 传递给Python进程的额外环境变量（如 MPLBACKEND=Agg），不能包含 RESERVED_ENV_VARS 中的变量。
+    // 默认设置 PYTHONUNBUFFERED=1，可在此覆盖
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
+
+    // 为 true 时传递 --output-stdout，由脚本将结果作为 RESULT_DATA 行写到stdout，
+    // 结果以文本返回（ProcessResult.output_data），不创建也不写入输出文件夹
+    #[serde(default)]
+    pub output_to_stdout: bool,
+
+    // 运行超时时间（秒），未设置时不限制
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    // 为 true 时，任务被取消或超时后删除本次运行新建的输出文件和目录（运行前已存在的不受影响）
+    #[serde(default)]
+    pub cleanup_on_abort: bool,
+    // 启动前按输入文件总大小的该倍数估计结果大小，输出磁盘剩余空间不足时不启动；为0时不检查
+    #[serde(default = "default_disk_space_factor")]
+    pub disk_space_factor: f64,
+
+    // Python因暂时性故障（如文件被临时占用）失败时的最大重试次数，默认不重试
+    #[serde(default)]
+    pub max_retries: u32,
+    // 首次重试前的等待时间（毫秒），之后每次加倍
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+
+    // 试运行：只返回将要处理的文件和输出位置，不启动Python
+    #[serde(default)]
+    pub dry_run: bool,
+
+    // 使用旧版逐项命令行参数传递配置（兼容不支持 --config 的 main.py）
+    #[serde(default)]
+    pub legacy_args: bool,
+
+    // 其他配置（可选，使用默认值）
+    #[serde(default)]
+    #[schemars(schema_with = "reference_channel_method_schema")]
+    pub reference_channel_method: String,
+    #[serde(default)]
+    pub verbose: bool,
+    // 为 true 时传递 --enable_progress_bar，由Python输出进度行
+    #[serde(default)]
+    pub enable_progress_bar: bool,
+    // 为 true 时回车符（\r）也作为行结束符，适用于用 \r 原地刷新进度的脚本
+    #[serde(default)]
+    pub line_buffer: bool,
+    // 无界面/脚本调用模式：不发送日志、进度等事件，并关闭 verbose 和 enable_progress_bar，只返回最终结果
+    #[serde(default)]
+    pub headless: bool,
+    // Python日志级别（error/warn/info/debug），为空时使用脚本默认级别
+    #[serde(default)]
+    #[schemars(schema_with = "log_level_schema")]
+    pub log_level: String,
+    // 为 true 时传递 --deterministic，由脚本固定随机数种子（未指定 seed 时为0），相同输入总是得到相同结果
+    #[serde(default)]
+    pub deterministic: bool,
+    // 异常检测使用的随机数种子，通过 --seed 传递，范围为 0 到 MAX_SEED
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    // 高级选项：原样追加在已知参数之后的额外命令行参数，便于试用应用尚未支持的 main.py 选项。
+    // 每项作为一个独立参数传递（不经过shell），不做任何转义或校验
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+fn default_retry_delay_ms() -> u64 {
+    1000
+}
+
+fn default_disk_space_factor() -> f64 {
+    2.0
+}
+
+// ProcessConfig 的JSON Schema，由结构体定义生成，供前端和外部工具生成表单、校验输入
+pub fn schema() -> RootSchema {
+    schemars::schema_for!(ProcessConfig)
+}
+
+// 只能取 values 中的值的字符串；allow_empty 为 true 时空字符串表示使用脚本默认值
+fn string_enum(values: &[&str], allow_empty: bool) -> Schema {
+    let empty = allow_empty.then_some("");
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(empty.into_iter().chain(values.iter().copied()).map(Into::into).collect()),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn outlier_method_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(OUTLIER_METHODS, false)
+}
+
+fn reference_channel_method_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(REFERENCE_CHANNEL_METHODS, true)
+}
+
+fn log_level_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(LOG_LEVELS, true)
+}
+
+impl ProcessConfig {
+    // 传给Python的日志级别，未设置或不支持时为 None
+    pub fn python_log_level(&self) -> Option<&'static str> {
+        PYTHON_LOG_LEVELS
+            .iter()
+            .find(|(level, _)| *level == self.log_level)
+            .map(|(_, python)| *python)
+    }
+
+    // 使用分析脚本默认参数的配置（阈值与 modules/config_parser.py 的默认值一致）
+    pub fn with_defaults(input_folder: String, output_folder: String) -> Self {
+        ProcessConfig {
+            input_folder,
+            output_folder,
+            overwrite_policy: OverwritePolicy::default(),
+            output_name_template: None,
+            input_folders: Vec::new(),
+            files: Vec::new(),
+            pattern: None,
+            fail_on_locked: false,
+            skip_invalid: false,
+            max_concurrent: None,
+            queue_order: QueueOrder::default(),
+            outlier_method: "boxplot".to_string(),
+            boxplot_threshold_discharge: 10.0,
+            boxplot_threshold_efficiency: 3.0,
+            zscore_threshold_discharge: 3.0,
+            zscore_threshold_efficiency: 2.5,
+            zscore_mad_constant: 0.6745,
+            cycle_sheet_name: None,
+            python_executable: None,
+            venv_path: None,
+            conda_env: None,
+            script_path: None,
+            working_dir: None,
+            env: None,
+            output_to_stdout: false,
+            timeout_secs: None,
+            cleanup_on_abort: false,
+            disk_space_factor: default_disk_space_factor(),
+            max_retries: 0,
+            retry_delay_ms: default_retry_delay_ms(),
+            dry_run: false,
+            legacy_args: false,
+            reference_channel_method: String::new(),
+            verbose: false,
+            enable_progress_bar: false,
+            line_buffer: false,
+            headless: false,
+            log_level: String::new(),
+            deterministic: false,
+            seed: None,
+            extra_args: Vec::new(),
+        }
+    }
+}
+
+// 校验参考通道选择方法，为空时使用脚本默认方法
+pub fn check_reference_channel_method(method: &str) -> Option<FieldError> {
+    if method.is_empty() || REFERENCE_CHANNEL_METHODS.contains(&method) {
+        return None;
+    }
+    Some(FieldError {
+        field: "reference_channel_method",
+        message: tr!(
+            "不支持的参考通道选择方法: \"{}\"（可选: {}）",
+            "Unsupported reference channel method: \"{}\" (available: {})",
+            method,
+            REFERENCE_CHANNEL_METHODS.join(", ")
+        ),
+    })
+}
+
+// 校验输出文件名模板：占位符必须成对且受支持，不能包含路径分隔符或文件名中不允许的字符
+fn check_output_name_template(template: &str) -> Option<FieldError> {
+    let error = |message: String| Some(FieldError { field: "output_name_template", message });
+    if template.trim().is_empty() {
+        return error(tr!("文件名模板不能为空", "File name template must not be empty"));
+    }
+    if let Some(c) = template.chars().find(|c| INVALID_FILENAME_CHARS.contains(c) || c.is_control()) {
+        return error(tr!(
+            "文件名模板包含不允许的字符: {:?}",
+            "File name template contains an invalid character: {:?}",
+            c
+        ));
+    }
+    if template.ends_with(['.', ' ']) {
+        return error(tr!(
+            "文件名模板不能以点或空格结尾",
+            "File name template must not end with a dot or space"
+        ));
+    }
+
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return error(tr!("文件名模板中有多余的 }}", "Unmatched }} in file name template"));
+        }
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(['{', '}']).filter(|&end| after[end..].starts_with('}')) else {
+            return error(tr!("文件名模板中有未闭合的 {{", "Unclosed {{ in file name template"));
+        };
+        let token = &after[..end];
+        if !OUTPUT_NAME_TOKENS.contains(&token) {
+            return error(tr!(
+                "不支持的占位符: {{{}}}（可选: {}）",
+                "Unsupported placeholder: {{{}}} (available: {})",
+                token,
+                OUTPUT_NAME_TOKENS.join(", ")
+            ));
+        }
+        rest = &after[end + 1..];
+    }
+    None
+}
+
+// 字段级校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ProcessConfig {
+    // 按 disk_space_factor 估计的结果大小（字节）
+    pub fn required_space(&self, input_bytes: u64) -> u64 {
+        (input_bytes as f64 * self.disk_space_factor).ceil() as u64
+    }
+
+    // 校验配置，返回所有字段级错误（为空表示配置有效）
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.input_folder.trim().is_empty() {
+            errors.push(FieldError {
+                field: "input_folder",
+                message: tr!("必须指定输入文件夹", "Input folder is required"),
+            });
+        }
+
+        if !OUTLIER_METHODS.contains(&self.outlier_method.as_str()) {
+            errors.push(FieldError {
+                field: "outlier_method",
+                message: tr!(
+                    "不支持的异常检测方法: \"{}\"（可选: {}）",
+                    "Unsupported outlier method: \"{}\" (available: {})",
+                    self.outlier_method,
+                    OUTLIER_METHODS.join(", ")
+                ),
+            });
+        }
+
+        errors.extend(check_reference_channel_method(&self.reference_channel_method));
+
+        if let Some(template) = &self.output_name_template {
+            errors.extend(check_output_name_template(template));
+        }
+
+        if let Err(e) = scan::file_pattern(self.pattern.as_deref()) {
+            errors.push(FieldError { field: "pattern", message: e.to_string() });
+        }
+
+        for file in &self.files {
+            // 分析脚本只读取 .xlsx 文件（见 main_processor.py 的 _input_files）
+            if FileKind::from_path(Path::new(file)) != Some(FileKind::Xlsx) {
+                errors.push(FieldError {
+                    field: "files",
+                    message: tr!(
+                        "不支持的文件类型: {}（仅支持 xlsx）",
+                        "Unsupported file type: {} (only xlsx is supported)",
+                        file
+                    ),
+                });
+            }
+        }
+
+        if !self.log_level.is_empty() && !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            errors.push(FieldError {
+                field: "log_level",
+                message: tr!(
+                    "不支持的日志级别: \"{}\"（可选: {}）",
+                    "Unsupported log level: \"{}\" (available: {})",
+                    self.log_level,
+                    LOG_LEVELS.join(", ")
+                ),
+            });
+        }
+
+        if !self.disk_space_factor.is_finite() || self.disk_space_factor < 0.0 {
+            errors.push(FieldError {
+                field: "disk_space_factor",
+                message: tr!(
+                    "必须为不小于0的有限数值，当前为 {}",
+                    "Must be a finite number not less than 0, got {}",
+                    self.disk_space_factor
+                ),
+            });
+        }
+
+        if let Some(seed) = self.seed.filter(|&seed| seed > MAX_SEED) {
+            errors.push(FieldError {
+                field: "seed",
+                message: tr!(
+                    "随机数种子必须在 0 到 {} 之间，当前为 {}",
+                    "Seed must be between 0 and {}, got {}",
+                    MAX_SEED,
+                    seed
+                ),
+            });
+        }
+
+        for key in self.env.iter().flat_map(|env| env.keys()) {
+            let message = if key.is_empty() || key.contains('=') || key.contains('\0') {
+                tr!("无效的环境变量名: \"{}\"", "Invalid environment variable name: \"{}\"", key)
+            } else if RESERVED_ENV_VARS.iter().any(|reserved| reserved.eq_ignore_ascii_case(key)) {
+                tr!(
+                    "环境变量 {} 由应用设置，不能覆盖",
+                    "Environment variable {} is set by the application and cannot be overridden",
+                    key
+                )
+            } else {
+                continue;
+            };
+            errors.push(FieldError { field: "env", message });
+        }
+
+        let thresholds = [
+            ("boxplot_threshold_discharge", self.boxplot_threshold_discharge),
+            ("boxplot_threshold_efficiency", self.boxplot_threshold_efficiency),
+            ("zscore_threshold_discharge", self.zscore_threshold_discharge),
+            ("zscore_threshold_efficiency", self.zscore_threshold_efficiency),
+            ("zscore_mad_constant", self.zscore_mad_constant),
+        ];
+        for (field, value) in thresholds {
+            if !value.is_finite() || value <= 0.0 {
+                errors.push(FieldError {
+                    field,
+                    message: tr!(
+                        "必须为大于0的有限数值，当前为 {}",
+                        "Must be a finite number greater than 0, got {}",
+                        value
+                    ),
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ProcessConfig {
+        ProcessConfig::with_defaults("/data/batch1".to_string(), String::new())
+    }
+
+    fn fields(config: &ProcessConfig) -> Vec<&'static str> {
+        config.validate().into_iter().map(|error| error.field).collect()
+    }
+
+    fn template_error(template: &str) -> bool {
+        check_output_name_template(template).is_some()
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(config().validate().is_empty());
+    }
+
+    #[test]
+    fn reports_every_invalid_field() {
+        let mut config = config();
+        config.input_folder = "  ".to_string();
+        config.outlier_method = "iqr".to_string();
+        config.reference_channel_method = "nearest".to_string();
+        config.pattern = Some("[".to_string());
+        config.files = vec!["/data/a.xlsx".to_string(), "/data/notes.txt".to_string()];
+        config.log_level = "trace".to_string();
+        config.disk_space_factor = -1.0;
+        config.seed = Some(MAX_SEED + 1);
+        config.boxplot_threshold_discharge = 0.0;
+        config.zscore_mad_constant = f64::NAN;
+        assert_eq!(
+            fields(&config),
+            vec![
+                "input_folder",
+                "outlier_method",
+                "reference_channel_method",
+                "pattern",
+                "files",
+                "log_level",
+                "disk_space_factor",
+                "seed",
+                "boxplot_threshold_discharge",
+                "zscore_mad_constant",
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_boundary_values() {
+        let mut config = config();
+        config.seed = Some(MAX_SEED);
+        config.disk_space_factor = 0.0;
+        config.log_level = "debug".to_string();
+        config.reference_channel_method = "pca".to_string();
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn rejects_reserved_and_invalid_env_vars() {
+        for key in ["PATH", "conda_prefix", "", "A=B"] {
+            let mut config = config();
+            config.env = Some(HashMap::from([(key.to_string(), "x".to_string())]));
+            assert_eq!(fields(&config), vec!["env"], "{:?}", key);
+        }
+        let mut config = config();
+        config.env = Some(HashMap::from([("MPLBACKEND".to_string(), "Agg".to_string())]));
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn checks_output_name_templates() {
+        assert!(!template_error("{input_name}_{date}"));
+        assert!(!template_error("结果-{method}-{time}"));
+        assert!(!template_error("summary"));
+        for template in ["", "  ", "a/b", "a:b", "result.", "{foo}", "{date", "date}", "{{date}}", "{}"] {
+            assert!(template_error(template), "{:?}", template);
+        }
+    }
+
+    #[test]
+    fn accepts_only_xlsx_files() {
+        let mut config = config();
+        config.files = vec!["/data/A1.XLSX".to_string()];
+        assert!(config.validate().is_empty());
+        config.files = vec!["/data/A1.xls".to_string(), "/data/A2.csv".to_string()];
+        assert_eq!(fields(&config), vec!["files", "files"]);
+    }
+
+    #[test]
+    fn maps_log_levels_to_python() {
+        let mut config = config();
+        assert_eq!(config.python_log_level(), None);
+        for (level, python) in [("error", "ERROR"), ("warn", "WARNING"), ("info", "INFO"), ("debug", "DEBUG")] {
+            config.log_level = level.to_string();
+            assert_eq!(config.python_log_level(), Some(python));
+        }
+        assert!(LOG_LEVELS.iter().all(|level| PYTHON_LOG_LEVELS.iter().any(|(l, _)| l == level)));
+    }
+
+    #[test]
+    fn required_space_scales_input_size() {
+        let mut config = config();
+        assert_eq!(config.required_space(1000), 2000);
+        config.disk_space_factor = 1.5;
+        assert_eq!(config.required_space(3), 5);
+        config.disk_space_factor = 0.0;
+        assert_eq!(config.required_space(1000), 0);
+    }
+}
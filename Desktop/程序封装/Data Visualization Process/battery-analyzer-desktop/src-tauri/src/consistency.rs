@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::csvfile;
+use crate::error::AppError;
+use crate::excel;
+use crate::scan::{self, FileKind, ScanOptions};
+
+// 最多检查的文件数，超出部分不检查（truncated 为 true）
+pub const MAX_CHECKED_FILES: usize = 500;
+
+// 一种表头组合及使用它的文件
+#[derive(Debug, Serialize)]
+pub struct HeaderSignature {
+    pub headers: Vec<String>,
+    pub files: Vec<String>,
+}
+
+// 与多数文件表头不同的文件，列出相对多数表头缺少和多出的列
+#[derive(Debug, Serialize)]
+pub struct HeaderDeviation {
+    pub file: String,
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
+}
+
+// 无法读取表头的文件
+#[derive(Debug, Serialize)]
+pub struct UnreadableFile {
+    pub file: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FolderConsistency {
+    // Excel文件检查的工作表
+    pub sheet: String,
+    pub checked: usize,
+    pub truncated: bool,
+    // 按使用的文件数从多到少排列，第一种即多数文件的表头
+    pub signatures: Vec<HeaderSignature>,
+    pub deviations: Vec<HeaderDeviation>,
+    pub unreadable: Vec<UnreadableFile>,
+    // 所有可读取的文件表头完全相同
+    pub consistent: bool,
+}
+
+// 读取文件夹中每个Excel/CSV文件的表头（不读取数据行），找出表头与多数文件不同的文件。
+// sheet 未指定时检查 Cycle 工作表，CSV文件没有工作表
+pub fn check_folder_consistency(folder: &str, sheet: Option<&str>) -> Result<FolderConsistency, AppError> {
+    scan::check_directory(folder)?;
+    let dir = Path::new(folder);
+    let sheet = sheet.filter(|s| !s.is_empty()).unwrap_or(excel::DEFAULT_CYCLE_SHEET);
+
+    let mut files = scan::scan_directory(dir, &ScanOptions::default())?;
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    let truncated = files.len() > MAX_CHECKED_FILES;
+    files.truncate(MAX_CHECKED_FILES);
+
+    let mut signatures: Vec<HeaderSignature> = Vec::new();
+    let mut unreadable = Vec::new();
+    for file in &files {
+        let headers = match file.kind {
+            FileKind::Csv => csvfile::read_headers(&file.path),
+            _ => excel::read_headers(&file.path, Some(sheet)),
+        };
+        let mut headers = match headers {
+            Ok(headers) => headers,
+            Err(e) => {
+                unreadable.push(UnreadableFile { file: file.name.clone(), error: e.to_string() });
+                continue;
+            }
+        };
+        // 末尾的空列不影响处理
+        while headers.last().is_some_and(String::is_empty) {
+            headers.pop();
+        }
+        match signatures.iter_mut().find(|signature| signature.headers == headers) {
+            Some(signature) => signature.files.push(file.name.clone()),
+            None => signatures.push(HeaderSignature { headers, files: vec![file.name.clone()] }),
+        }
+    }
+    // 稳定排序：文件数相同时先出现的表头排在前面
+    signatures.sort_by_key(|signature| std::cmp::Reverse(signature.files.len()));
+
+    let deviations = match signatures.split_first() {
+        Some((majority, others)) => {
+            let expected: HashSet<&str> = majority.headers.iter().map(String::as_str).collect();
+            others
+                .iter()
+                .flat_map(|signature| {
+                    let actual: HashSet<&str> = signature.headers.iter().map(String::as_str).collect();
+                    let missing: Vec<String> = majority
+                        .headers
+                        .iter()
+                        .filter(|header| !actual.contains(header.as_str()))
+                        .cloned()
+                        .collect();
+                    let extra: Vec<String> = signature
+                        .headers
+                        .iter()
+                        .filter(|header| !expected.contains(header.as_str()))
+                        .cloned()
+                        .collect();
+                    signature.files.iter().map(move |file| HeaderDeviation {
+                        file: file.clone(),
+                        missing: missing.clone(),
+                        extra: extra.clone(),
+                    })
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    Ok(FolderConsistency {
+        sheet: sheet.to_string(),
+        checked: files.len(),
+        truncated,
+        consistent: signatures.len() <= 1,
+        signatures,
+        deviations,
+        unreadable,
+    })
+}
@@ -0,0 +1,116 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use encoding_rs::{Encoding, GBK, UTF_8};
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::excel::MAX_PREVIEW_ROWS;
+
+// 读取表头时最多读取的字节数
+const HEADER_BYTES: u64 = 64 * 1024;
+
+// CSV文件预览，字段与 ExcelPreview 一致（CSV没有工作表）
+#[derive(Debug, Serialize)]
+pub struct CsvPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    // 数据行总数（不含表头）
+    pub total_rows: usize,
+    pub truncated: bool,
+    // 实际使用的编码（如 "UTF-8"、"GBK"、"UTF-16LE"）
+    pub detected_encoding: String,
+    // 文件开头有字节顺序标记（BOM），已去除
+    pub bom: bool,
+}
+
+// 读取CSV文件的表头和前 rows 行（最多 MAX_PREVIEW_ROWS 行）。
+// encoding 未指定时按BOM判断，没有BOM时是合法的UTF-8则按UTF-8读取，否则按GBK读取（中文系统下仪器软件常用）
+pub fn preview(path: &str, encoding: Option<&str>, rows: usize) -> Result<CsvPreview, AppError> {
+    if !Path::new(path).is_file() {
+        return Err(AppError::FileNotFound(path.to_string()));
+    }
+    let bytes = fs::read(path).map_err(|e| AppError::io("读取CSV文件失败", e))?;
+    let (text, encoding, bom) = decode(&bytes, encoding.filter(|e| !e.trim().is_empty()))?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut records = reader.records();
+    let invalid = |e: csv::Error| AppError::InvalidWorkbook {
+        path: path.to_string(),
+        reason: e.to_string(),
+    };
+    let headers = match records.next() {
+        Some(record) => record.map_err(invalid)?.iter().map(str::to_string).collect(),
+        None => Vec::new(),
+    };
+
+    let limit = rows.min(MAX_PREVIEW_ROWS);
+    let mut preview_rows = Vec::new();
+    let mut total_rows = 0;
+    for record in records {
+        let record = record.map_err(invalid)?;
+        if total_rows < limit {
+            preview_rows.push(record.iter().map(str::to_string).collect());
+        }
+        total_rows += 1;
+    }
+
+    Ok(CsvPreview {
+        headers,
+        rows: preview_rows,
+        total_rows,
+        truncated: total_rows > limit,
+        detected_encoding: encoding.name().to_string(),
+        bom,
+    })
+}
+
+// 只读取CSV文件的表头行（按 preview 相同的规则识别编码）
+pub fn read_headers(path: &str) -> Result<Vec<String>, AppError> {
+    let file = File::open(path).map_err(|e| AppError::io("读取CSV文件失败", e))?;
+    let mut bytes = Vec::new();
+    file.take(HEADER_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| AppError::io("读取CSV文件失败", e))?;
+    // 截到最后一个换行，避免截断的多字节字符影响编码识别
+    if let Some(end) = bytes.iter().rposition(|&b| b == b'\n') {
+        bytes.truncate(end + 1);
+    }
+    let (text, _, _) = decode(&bytes, None)?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    match reader.records().next() {
+        Some(Ok(record)) => Ok(record.iter().map(|field| field.trim().to_string()).collect()),
+        Some(Err(e)) => Err(AppError::InvalidWorkbook { path: path.to_string(), reason: e.to_string() }),
+        None => Ok(Vec::new()),
+    }
+}
+
+// 解码文件内容，返回文本、使用的编码以及是否去除了BOM
+fn decode(bytes: &[u8], label: Option<&str>) -> Result<(String, &'static Encoding, bool), AppError> {
+    let bom = Encoding::for_bom(bytes);
+    let (encoding, content) = match (label, bom) {
+        (Some(label), _) => {
+            let encoding = Encoding::for_label(label.trim().as_bytes())
+                .ok_or_else(|| AppError::UnsupportedEncoding(label.to_string()))?;
+            // 指定的编码与BOM一致时同样去除BOM
+            match bom {
+                Some((bom_encoding, len)) if bom_encoding == encoding => (encoding, &bytes[len..]),
+                _ => (encoding, bytes),
+            }
+        }
+        (None, Some((encoding, len))) => (encoding, &bytes[len..]),
+        (None, None) if std::str::from_utf8(bytes).is_ok() => (UTF_8, bytes),
+        (None, None) => (GBK, bytes),
+    };
+    let stripped = content.len() < bytes.len();
+    let (text, _) = encoding.decode_without_bom_handling(content);
+    Ok((text.into_owned(), encoding, stripped))
+}
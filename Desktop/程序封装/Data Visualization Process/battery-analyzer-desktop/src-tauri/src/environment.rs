@@ -0,0 +1,213 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::AppError;
+use crate::i18n::tr;
+use crate::python::{self, PythonRuntime};
+use crate::script;
+
+// 分析脚本依赖的Python包（模块名, pip包名），与 自动安装依赖.bat 安装的包一致（可选的 xlsxwriter 除外）。
+// scipy 随 scikit-learn 安装，异常检测直接导入，单独检查
+const REQUIRED_PACKAGES: &[(&str, &str)] = &[
+    ("numpy", "numpy"),
+    ("pandas", "pandas"),
+    ("matplotlib", "matplotlib"),
+    ("seaborn", "seaborn"),
+    ("openpyxl", "openpyxl"),
+    ("python_calamine", "python-calamine"),
+    ("sklearn", "scikit-learn"),
+    ("scipy", "scipy"),
+    ("tqdm", "tqdm"),
+];
+
+// 检查缺失包的Python代码：逐个探测传入的模块名，输出找不到的模块
+const FIND_MISSING_SNIPPET: &str =
+    "import importlib.util, sys\nfor name in sys.argv[1:]:\n    if importlib.util.find_spec(name) is None:\n        print(name)";
+
+// 运行环境检查结果
+#[derive(Debug, Serialize)]
+pub struct EnvironmentStatus {
+    pub python_path: Option<String>,
+    pub python_version: Option<String>,
+    // 缺失的包（pip包名，可直接用于 pip install）
+    pub missing_packages: Vec<String>,
+    pub script_found: bool,
+    pub script_path: Option<String>,
+    // 未能找到或运行Python时的原因
+    pub python_error: Option<String>,
+}
+
+// 检查Python解释器、依赖包和分析脚本是否就绪
+pub fn check(
+    app: &AppHandle,
+    python_executable: Option<&str>,
+    venv_path: Option<&str>,
+    conda_env: Option<&str>,
+    script_path: Option<&str>,
+) -> EnvironmentStatus {
+    let script_path = script::resolve_script(app, script_path)
+        .ok()
+        .map(|path| path.to_string_lossy().to_string());
+    let script_found = script_path.is_some();
+
+    let runtime = match python::resolve_runtime(python_executable, venv_path, conda_env) {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            return EnvironmentStatus {
+                python_path: None,
+                python_version: None,
+                missing_packages: Vec::new(),
+                script_found,
+                script_path,
+                python_error: Some(AppError::from(e).to_string()),
+            }
+        }
+    };
+
+    let python_path = Some(runtime.executable.to_string_lossy().to_string());
+    let python_version = match python_version(&runtime) {
+        Ok(version) => version,
+        Err(e) => {
+            return EnvironmentStatus {
+                python_path,
+                python_version: None,
+                missing_packages: Vec::new(),
+                script_found,
+                script_path,
+                python_error: Some(e),
+            }
+        }
+    };
+
+    let (missing_packages, python_error) = match missing_packages(&runtime) {
+        Ok(missing) => (missing, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+
+    EnvironmentStatus {
+        python_path,
+        python_version: Some(python_version),
+        missing_packages,
+        script_found,
+        script_path,
+        python_error,
+    }
+}
+
+// 应用与分析环境的版本信息，便于附加到问题报告
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub app_version: String,
+    pub tauri_version: String,
+    // 未找到或无法运行Python时为 None
+    pub python_version: Option<String>,
+    // main.py --version 的输出，脚本不支持该参数时为 None
+    pub script_version: Option<String>,
+}
+
+// 版本信息在本次运行中只检测一次
+static VERSION_INFO: OnceLock<VersionInfo> = OnceLock::new();
+
+// 获取版本信息，使用默认的Python解释器和分析脚本
+pub fn version_info(app: &AppHandle) -> VersionInfo {
+    VERSION_INFO
+        .get_or_init(|| {
+            let runtime = python::resolve_runtime(None, None, None).ok();
+            let python_version = runtime.as_ref().and_then(|runtime| python_version(runtime).ok());
+            let script = script::resolve_script(app, None).ok();
+            let script_version = runtime
+                .as_ref()
+                .zip(script.as_deref())
+                .and_then(|(runtime, script)| script_version(runtime, script));
+            VersionInfo {
+                app_version: app.package_info().version.to_string(),
+                tauri_version: tauri::VERSION.to_string(),
+                python_version,
+                script_version,
+            }
+        })
+        .clone()
+}
+
+// 构建模式与运行平台
+#[derive(Debug, Serialize)]
+pub struct RuntimeInfo {
+    // 调试构建（开发模式）时为 true
+    pub debug: bool,
+    // std::env::consts::OS，如 "windows"、"macos"、"linux"
+    pub platform: String,
+    // std::env::consts::ARCH，如 "x86_64"、"aarch64"
+    pub arch: String,
+}
+
+pub fn runtime_info() -> RuntimeInfo {
+    RuntimeInfo {
+        debug: cfg!(debug_assertions),
+        platform: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+    }
+}
+
+// 分析脚本 --version 的输出（第一行非空内容）
+pub fn script_version(runtime: &PythonRuntime, script: &Path) -> Option<String> {
+    let output = runtime.command().arg(script).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let version = if stdout.trim().is_empty() { stderr } else { stdout };
+    version
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+// python --version（Python 3.4 之前输出到stderr）
+pub fn python_version(runtime: &PythonRuntime) -> Result<String, String> {
+    let output = runtime
+        .command()
+        .arg("--version")
+        .output()
+        .map_err(|e| tr!("运行Python失败: {}", "Failed to run Python: {}", e))?;
+    if !output.status.success() {
+        return Err(tr!(
+            "Python运行异常: {}",
+            "Python exited abnormally: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let version = if stdout.trim().is_empty() { stderr } else { stdout };
+    Ok(version.trim().to_string())
+}
+
+fn missing_packages(runtime: &PythonRuntime) -> Result<Vec<String>, String> {
+    let output = runtime
+        .command()
+        .arg("-c")
+        .arg(FIND_MISSING_SNIPPET)
+        .args(REQUIRED_PACKAGES.iter().map(|(module, _)| module))
+        .output()
+        .map_err(|e| tr!("检查Python依赖失败: {}", "Failed to check Python packages: {}", e))?;
+    if !output.status.success() {
+        return Err(tr!(
+            "检查Python依赖失败: {}",
+            "Failed to check Python packages: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter_map(|module| REQUIRED_PACKAGES.iter().find(|(name, _)| *name == module))
+        .map(|(_, package)| package.to_string())
+        .collect())
+}
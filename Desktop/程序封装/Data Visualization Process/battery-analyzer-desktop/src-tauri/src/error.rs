@@ -0,0 +1,461 @@
+use std::fmt;
+use std::io;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::analysis::AnalysisError;
+use crate::config::FieldError;
+use crate::i18n::{self, tr};
+use crate::python::PythonLookupError;
+use crate::scan;
+
+// 命令返回给前端的错误类型；序列化为 { kind, message, ...附加字段 }
+#[derive(Debug)]
+pub enum AppError {
+    InvalidConfig(Vec<FieldError>),
+    InputFolderMissing(String),
+    DirectoryNotFound(String),
+    // 路径存在但不是文件夹（如误选了文件）
+    NotADirectory(String),
+    PythonNotFound { tried: Vec<String> },
+    PythonNotExecutable(String),
+    PythonEnvInvalid(String),
+    CondaEnvNotFound(String),
+    // 附带所有尝试过的脚本路径
+    ScriptNotFound(Vec<String>),
+    SpawnFailed(io::Error),
+    // code 为退出码，被信号终止时 code 为 None、signal 为信号编号（仅Unix）
+    // errors 为Python脚本报告的结构化错误，没有时前端显示原始 stderr。
+    // stdout 为失败前已输出的内容，便于判断处理到了哪个文件
+    ProcessFailed {
+        code: Option<i32>,
+        signal: Option<i32>,
+        stdout: String,
+        stderr: String,
+        errors: Vec<AnalysisError>,
+    },
+    Timeout { elapsed_secs: u64, stdout: String, stderr: String },
+    Cancelled,
+    // 覆盖策略为 Fail 且输出文件夹中已有文件
+    OutputConflict { folder: String, files: Vec<String> },
+    FilesLocked(Vec<String>),
+    // 输入文件为空或小于有效文件的最小大小
+    InvalidInputFiles(Vec<String>),
+    // 文件名通配符语法错误
+    InvalidPattern { pattern: String, reason: String },
+    // 没有与通配符匹配的数据文件
+    NoMatchingFiles(String),
+    // 输出文件夹存在但无法写入
+    OutputNotWritable { path: String, reason: String },
+    // 输出文件夹所在磁盘的剩余空间少于估计的结果大小（字节）
+    InsufficientDiskSpace { path: String, required: u64, available: u64 },
+    // 无法监听文件夹变化
+    WatchFailed { path: String, reason: String },
+    FileNotFound(String),
+    // 文件夹中没有分析结果工作簿
+    ResultNotFound(String),
+    // A1格式区域无效或过大
+    InvalidRange { range: String, reason: String },
+    // 工作簿格式转换失败
+    ConversionFailed { path: String, reason: String },
+    MergeFailed { path: String, reason: String },
+    // 没有可打开该文件的默认程序
+    NoDefaultApp(String),
+    PathNotFound(String),
+    // 无法用系统程序打开路径
+    OpenFailed { path: String, reason: String },
+    // 文件不是有效的Excel工作簿或已损坏
+    InvalidWorkbook { path: String, reason: String },
+    SheetNotFound(String),
+    ColumnsNotFound { sheet: String, columns: Vec<String> },
+    InvalidPresetName(String),
+    PresetNotFound(String),
+    UnsupportedLocale(String),
+    // encoding_rs 无法识别的编码名称
+    UnsupportedEncoding(String),
+    // 无法解析应用数据目录
+    AppDirUnavailable(String),
+    // 后台任务异常退出（如线程panic）
+    TaskFailed(String),
+    IoError { context: String, source: io::Error },
+}
+
+// stderr中表示暂时性故障（文件被杀毒软件等临时占用、网络共享不稳定）的特征
+const TRANSIENT_PATTERNS: &[&str] = &[
+    "PermissionError",
+    "BlockingIOError",
+    "TimeoutError",
+    "ConnectionError",
+    "[Errno 11]",
+    "[Errno 13]",
+    "[WinError 32]",
+    "[WinError 33]",
+    "being used by another process",
+    "另一个程序正在使用此文件",
+];
+
+impl AppError {
+    // 带上下文说明的IO错误
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        AppError::IoError { context: context.into(), source }
+    }
+
+    // Python因暂时性故障失败、值得重试的错误；配置、环境等错误重试也不会成功
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::ProcessFailed { stderr, .. } => {
+                TRANSIENT_PATTERNS.iter().any(|pattern| stderr.contains(pattern))
+            }
+            _ => false,
+        }
+    }
+
+    // 与语言无关的错误类别，供前端判断
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::InvalidConfig(_) => "InvalidConfig",
+            AppError::InputFolderMissing(_) => "InputFolderMissing",
+            AppError::DirectoryNotFound(_) => "DirectoryNotFound",
+            AppError::NotADirectory(_) => "NotADirectory",
+            AppError::PythonNotFound { .. } => "PythonNotFound",
+            AppError::PythonNotExecutable(_) => "PythonNotExecutable",
+            AppError::PythonEnvInvalid(_) => "PythonEnvInvalid",
+            AppError::CondaEnvNotFound(_) => "CondaEnvNotFound",
+            AppError::ScriptNotFound(_) => "ScriptNotFound",
+            AppError::SpawnFailed(_) => "SpawnFailed",
+            AppError::ProcessFailed { .. } => "ProcessFailed",
+            AppError::Timeout { .. } => "Timeout",
+            AppError::Cancelled => "Cancelled",
+            AppError::OutputConflict { .. } => "OutputConflict",
+            AppError::FilesLocked(_) => "FilesLocked",
+            AppError::InvalidInputFiles(_) => "InvalidInputFiles",
+            AppError::InvalidPattern { .. } => "InvalidPattern",
+            AppError::NoMatchingFiles(_) => "NoMatchingFiles",
+            AppError::OutputNotWritable { .. } => "OutputNotWritable",
+            AppError::InsufficientDiskSpace { .. } => "InsufficientDiskSpace",
+            AppError::WatchFailed { .. } => "WatchFailed",
+            AppError::FileNotFound(_) => "FileNotFound",
+            AppError::ResultNotFound(_) => "ResultNotFound",
+            AppError::InvalidRange { .. } => "InvalidRange",
+            AppError::ConversionFailed { .. } => "ConversionFailed",
+            AppError::MergeFailed { .. } => "MergeFailed",
+            AppError::NoDefaultApp(_) => "NoDefaultApp",
+            AppError::PathNotFound(_) => "PathNotFound",
+            AppError::OpenFailed { .. } => "OpenFailed",
+            AppError::InvalidWorkbook { .. } => "InvalidWorkbook",
+            AppError::SheetNotFound(_) => "SheetNotFound",
+            AppError::ColumnsNotFound { .. } => "ColumnsNotFound",
+            AppError::InvalidPresetName(_) => "InvalidPresetName",
+            AppError::PresetNotFound(_) => "PresetNotFound",
+            AppError::UnsupportedLocale(_) => "UnsupportedLocale",
+            AppError::UnsupportedEncoding(_) => "UnsupportedEncoding",
+            AppError::AppDirUnavailable(_) => "AppDirUnavailable",
+            AppError::TaskFailed(_) => "TaskFailed",
+            AppError::IoError { .. } => "IoError",
+        }
+    }
+}
+
+// 错误信息按当前语言（set_locale）生成，kind 与语言无关
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            AppError::InvalidConfig(errors) => {
+                let mut message = tr!("配置无效:", "Invalid configuration:");
+                for error in errors {
+                    message.push_str(&format!("\n  {}: {}", error.field, error.message));
+                }
+                message
+            }
+            AppError::InputFolderMissing(_) => tr!("输入文件夹不存在", "Input folder does not exist"),
+            AppError::DirectoryNotFound(_) => tr!("文件夹不存在", "Folder does not exist"),
+            AppError::NotADirectory(path) => tr!(
+                "所选路径是文件而不是文件夹: {}",
+                "The selected path is a file, not a folder: {}",
+                path
+            ),
+            AppError::PythonNotFound { tried } => tr!(
+                "❌ 未找到Python解释器（已尝试: {}），请安装Python或在配置中指定解释器路径",
+                "❌ Python interpreter not found (tried: {}). Install Python or set the interpreter path in the configuration",
+                tried.join(", ")
+            ),
+            AppError::PythonNotExecutable(path) => tr!(
+                "❌ Python解释器不可执行: {}",
+                "❌ Python interpreter is not executable: {}",
+                path
+            ),
+            AppError::PythonEnvInvalid(path) => tr!(
+                "❌ 环境目录中未找到Python解释器: {}",
+                "❌ No Python interpreter found in environment: {}",
+                path
+            ),
+            AppError::CondaEnvNotFound(name) => {
+                tr!("❌ 未找到conda环境: {}", "❌ Conda environment not found: {}", name)
+            }
+            AppError::ScriptNotFound(tried) => tr!(
+                "Python脚本不存在: {}",
+                "Python script not found: {}",
+                tried.join(", ")
+            ),
+            AppError::SpawnFailed(e) => {
+                tr!("❌ 启动Python脚本失败: {}", "❌ Failed to start the Python script: {}", e)
+            }
+            AppError::ProcessFailed { code, signal, stdout, stderr, errors } => {
+                let mut message = match (code, signal) {
+                    (Some(code), _) => tr!(
+                        "❌ Python脚本执行失败（退出码 {}）",
+                        "❌ Python script failed (exit code {})",
+                        code
+                    ),
+                    (None, Some(signal)) => tr!(
+                        "❌ Python脚本被信号 {} 终止",
+                        "❌ Python script was terminated by signal {}",
+                        signal
+                    ),
+                    (None, None) => tr!("❌ Python脚本执行失败", "❌ Python script failed"),
+                };
+                if errors.is_empty() {
+                    message.push_str(&format!(":\n{}", stderr));
+                }
+                for error in errors {
+                    message.push_str(&format!("\n{}", error));
+                }
+                if let Some(last) = stdout.lines().rev().find(|line| !line.trim().is_empty()) {
+                    message.push_str(&tr!("\n\n最后的输出: {}", "\n\nLast output: {}", last.trim()));
+                }
+                message
+            }
+            AppError::Timeout { elapsed_secs, stderr, .. } => tr!(
+                "⏱️ Python脚本运行超时，已运行 {} 秒后被终止\n\n已收集的错误输出:\n{}",
+                "⏱️ Python script timed out and was terminated after {} seconds\n\nCollected error output:\n{}",
+                elapsed_secs,
+                stderr
+            ),
+            AppError::Cancelled => tr!("⚠️ 数据处理已取消", "⚠️ Processing was cancelled"),
+            AppError::FilesLocked(files) => tr!(
+                "以下文件正被其他程序（如Excel）占用，请关闭后重试:\n{}",
+                "The following files are in use by another program (such as Excel). Close them and try again:\n{}",
+                files.join("\n")
+            ),
+            AppError::InvalidInputFiles(files) => tr!(
+                "以下输入文件为空或过小，可能是导出失败留下的文件（设置 skip_invalid 可跳过）:\n{}",
+                "The following input files are empty or too small, possibly left by a failed export (set skip_invalid to skip them):\n{}",
+                files.join("\n")
+            ),
+            AppError::InvalidPattern { pattern, reason } => tr!(
+                "无效的文件名通配符 \"{}\": {}",
+                "Invalid file name pattern \"{}\": {}",
+                pattern,
+                reason
+            ),
+            AppError::NoMatchingFiles(pattern) => tr!(
+                "没有与 \"{}\" 匹配的数据文件",
+                "No data files match \"{}\"",
+                pattern
+            ),
+            AppError::OutputConflict { folder, files } => tr!(
+                "输出文件夹 {} 中已有 {} 个文件，可能被覆盖:\n{}",
+                "Output folder {} already contains {} files that may be overwritten:\n{}",
+                folder,
+                files.len(),
+                files.join("\n")
+            ),
+            AppError::OutputNotWritable { path, reason } => tr!(
+                "❌ 输出文件夹不可写入: {} ({})",
+                "❌ Output folder is not writable: {} ({})",
+                path,
+                reason
+            ),
+            AppError::InsufficientDiskSpace { path, required, available } => tr!(
+                "❌ 输出文件夹所在磁盘空间不足: {}（预计需要 {}，剩余 {}；可调整 disk_space_factor）",
+                "❌ Not enough disk space for output folder: {} (about {} needed, {} available; see disk_space_factor)",
+                path,
+                scan::format_size(*required),
+                scan::format_size(*available)
+            ),
+            AppError::WatchFailed { path, reason } => tr!(
+                "无法监听文件夹 {}: {}",
+                "Cannot watch folder {}: {}",
+                path,
+                reason
+            ),
+            AppError::FileNotFound(path) => tr!("文件不存在: {}", "File not found: {}", path),
+            AppError::ResultNotFound(path) => tr!(
+                "文件夹中没有分析结果文件（电池数据汇总表-*.xlsx）: {}",
+                "No analysis result file (电池数据汇总表-*.xlsx) in folder: {}",
+                path
+            ),
+            AppError::InvalidRange { range, reason } => tr!(
+                "无效的单元格区域 \"{}\": {}",
+                "Invalid cell range \"{}\": {}",
+                range,
+                reason
+            ),
+            AppError::ConversionFailed { path, reason } => tr!(
+                "转换为xlsx失败 {}: {}",
+                "Failed to convert to xlsx {}: {}",
+                path,
+                reason
+            ),
+            AppError::MergeFailed { path, reason } => tr!(
+                "合并Excel文件失败 {}: {}",
+                "Failed to merge Excel files into {}: {}",
+                path,
+                reason
+            ),
+            AppError::NoDefaultApp(path) => tr!(
+                "没有可以打开该文件的默认程序: {}",
+                "No default application can open this file: {}",
+                path
+            ),
+            AppError::PathNotFound(path) => tr!("路径不存在: {}", "Path does not exist: {}", path),
+            AppError::OpenFailed { path, reason } => {
+                tr!("无法打开 {}: {}", "Cannot open {}: {}", path, reason)
+            }
+            AppError::InvalidWorkbook { path, reason } => tr!(
+                "无法读取Excel文件 {}: {}",
+                "Cannot read Excel file {}: {}",
+                path,
+                reason
+            ),
+            AppError::SheetNotFound(name) => tr!("工作表不存在: {}", "Sheet not found: {}", name),
+            AppError::ColumnsNotFound { sheet, columns } => tr!(
+                "工作表 {} 中没有以下列: {}",
+                "Columns not found in sheet {}: {}",
+                sheet,
+                columns.join(", ")
+            ),
+            AppError::InvalidPresetName(name) => {
+                tr!("预设名称无效: \"{}\"", "Invalid preset name: \"{}\"", name)
+            }
+            AppError::PresetNotFound(name) => tr!("预设不存在: {}", "Preset not found: {}", name),
+            AppError::UnsupportedLocale(lang) => tr!(
+                "不支持的语言: {}（可选: zh, en）",
+                "Unsupported language: {} (available: zh, en)",
+                lang
+            ),
+            AppError::UnsupportedEncoding(encoding) => tr!(
+                "不支持的编码: {}（如 utf-8, gbk, gb18030）",
+                "Unsupported encoding: {} (e.g. utf-8, gbk, gb18030)",
+                encoding
+            ),
+            AppError::AppDirUnavailable(reason) => tr!(
+                "无法定位应用数据目录: {}",
+                "Cannot locate the application data folder: {}",
+                reason
+            ),
+            AppError::TaskFailed(reason) => {
+                tr!("后台任务执行失败: {}", "Background task failed: {}", reason)
+            }
+            AppError::IoError { context, source } => {
+                format!("{}: {}", i18n::io_context(context), source)
+            }
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::SpawnFailed(e) | AppError::IoError { source: e, .. } => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<PythonLookupError> for AppError {
+    fn from(e: PythonLookupError) -> Self {
+        match e {
+            PythonLookupError::NotFound { tried } => AppError::PythonNotFound { tried },
+            PythonLookupError::NotExecutable(path) => {
+                AppError::PythonNotExecutable(path.to_string_lossy().to_string())
+            }
+            PythonLookupError::EnvMissingPython(path) => {
+                AppError::PythonEnvInvalid(path.to_string_lossy().to_string())
+            }
+            PythonLookupError::CondaEnvNotFound(name) => AppError::CondaEnvNotFound(name),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            AppError::InvalidConfig(errors) => map.serialize_entry("errors", errors)?,
+            AppError::InputFolderMissing(path)
+            | AppError::DirectoryNotFound(path)
+            | AppError::NotADirectory(path)
+            | AppError::PythonNotExecutable(path)
+            | AppError::PythonEnvInvalid(path)
+            | AppError::FileNotFound(path)
+            | AppError::ResultNotFound(path)
+            | AppError::NoDefaultApp(path)
+            | AppError::PathNotFound(path) => map.serialize_entry("path", path)?,
+            AppError::InvalidWorkbook { path, reason }
+            | AppError::OpenFailed { path, reason }
+            | AppError::OutputNotWritable { path, reason }
+            | AppError::WatchFailed { path, reason }
+            | AppError::ConversionFailed { path, reason }
+            | AppError::MergeFailed { path, reason } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("reason", reason)?;
+            }
+            AppError::InsufficientDiskSpace { path, required, available } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("required", required)?;
+                map.serialize_entry("available", available)?;
+            }
+            AppError::SheetNotFound(name) => map.serialize_entry("sheet", name)?,
+            AppError::ColumnsNotFound { sheet, columns } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("columns", columns)?;
+            }
+            AppError::InvalidRange { range, reason } => {
+                map.serialize_entry("range", range)?;
+                map.serialize_entry("reason", reason)?;
+            }
+            AppError::InvalidPattern { pattern, reason } => {
+                map.serialize_entry("pattern", pattern)?;
+                map.serialize_entry("reason", reason)?;
+            }
+            AppError::NoMatchingFiles(pattern) => map.serialize_entry("pattern", pattern)?,
+            AppError::FilesLocked(files) | AppError::InvalidInputFiles(files) => {
+                map.serialize_entry("files", files)?
+            }
+            AppError::OutputConflict { folder, files } => {
+                map.serialize_entry("folder", folder)?;
+                map.serialize_entry("files", files)?;
+            }
+            AppError::ScriptNotFound(tried) => map.serialize_entry("tried", tried)?,
+            AppError::CondaEnvNotFound(name) => map.serialize_entry("env", name)?,
+            AppError::InvalidPresetName(name) | AppError::PresetNotFound(name) => {
+                map.serialize_entry("name", name)?
+            }
+            AppError::PythonNotFound { tried } => map.serialize_entry("tried", tried)?,
+            AppError::UnsupportedLocale(lang) => map.serialize_entry("lang", lang)?,
+            AppError::UnsupportedEncoding(encoding) => map.serialize_entry("encoding", encoding)?,
+            AppError::ProcessFailed { code, signal, stdout, stderr, errors } => {
+                map.serialize_entry("code", code)?;
+                map.serialize_entry("signal", signal)?;
+                map.serialize_entry("stdout", stdout)?;
+                map.serialize_entry("stderr", stderr)?;
+                map.serialize_entry("analysis_errors", errors)?;
+            }
+            AppError::Timeout { elapsed_secs, stdout, stderr } => {
+                map.serialize_entry("elapsed_secs", elapsed_secs)?;
+                map.serialize_entry("stdout", stdout)?;
+                map.serialize_entry("stderr", stderr)?;
+            }
+            AppError::SpawnFailed(_)
+            | AppError::Cancelled
+            | AppError::AppDirUnavailable(_)
+            | AppError::TaskFailed(_)
+            | AppError::IoError { .. } => {}
+        }
+        map.end()
+    }
+}
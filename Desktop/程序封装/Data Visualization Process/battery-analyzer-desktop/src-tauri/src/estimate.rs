@@ -0,0 +1,142 @@
+use serde::Serialize;
+
+use crate::config::{ProcessConfig, QueueOrder};
+use crate::error::AppError;
+use crate::excel;
+use crate::runner;
+
+// 单个输入文件的处理成本估计
+#[derive(Debug, Serialize)]
+pub struct FileEstimate {
+    pub input_folder: String,
+    pub path: String,
+    pub size: u64,
+    // 循环数据工作表的数据行数，仅在请求时统计（CSV或无法读取时为 None）
+    pub rows: Option<usize>,
+}
+
+// 列出 config 将要处理的所有输入文件（批量处理时包括 input_folders 中的每个文件夹）及其大小，
+// count_rows 为 true 时另外读取每个Excel文件的行数。结果按 queue_order 排序（见 sort_estimates）
+pub fn estimate_jobs(config: &ProcessConfig, count_rows: bool) -> Result<Vec<FileEstimate>, AppError> {
+    let sheet = config
+        .cycle_sheet_name
+        .as_deref()
+        .filter(|sheet| !sheet.is_empty())
+        .unwrap_or(excel::DEFAULT_CYCLE_SHEET);
+
+    let mut estimates = Vec::new();
+    for folder_config in folder_configs(config) {
+        for (path, size, kind) in runner::input_files(&folder_config)? {
+            let rows = (count_rows && kind.is_excel())
+                .then(|| row_count(&path, sheet))
+                .flatten();
+            estimates.push(FileEstimate {
+                input_folder: folder_config.input_folder.clone(),
+                path,
+                size,
+                rows,
+            });
+        }
+    }
+    sort_estimates(config.queue_order, &mut estimates);
+    Ok(estimates)
+}
+
+// 所有文件都统计了行数时按行数排序，否则（如有CSV文件）按文件大小，
+// 避免没有行数的文件总是排在最前面
+fn sort_estimates(order: QueueOrder, estimates: &mut [FileEstimate]) {
+    if estimates.iter().all(|estimate| estimate.rows.is_some()) {
+        order.sort_by_cost(estimates, |estimate| (estimate.rows, estimate.size));
+    } else {
+        order.sort_by_cost(estimates, |estimate| estimate.size);
+    }
+}
+
+// 任务的成本：所有输入文件的总大小，无法读取输入时为0
+pub fn job_cost(config: &ProcessConfig) -> u64 {
+    folder_configs(config)
+        .iter()
+        .filter_map(|folder_config| runner::input_files(folder_config).ok())
+        .flatten()
+        .map(|(_, size, _)| size)
+        .sum()
+}
+
+// 按文件夹拆分配置：input_folders 为空时只有 input_folder
+fn folder_configs(config: &ProcessConfig) -> Vec<ProcessConfig> {
+    if config.input_folders.is_empty() {
+        return vec![config.clone()];
+    }
+    config
+        .input_folders
+        .iter()
+        .map(|folder| {
+            let mut folder_config = config.clone();
+            folder_config.input_folder = folder.clone();
+            folder_config.input_folders.clear();
+            folder_config
+        })
+        .collect()
+}
+
+fn row_count(path: &str, sheet: &str) -> Option<usize> {
+    let mut workbook = excel::open_workbook(path).ok()?;
+    let (_, range) = excel::read_sheet(&mut workbook, path, Some(sheet)).ok()?;
+    Some(range.height().saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(path: &str, size: u64, rows: Option<usize>) -> FileEstimate {
+        FileEstimate {
+            input_folder: "/data".to_string(),
+            path: path.to_string(),
+            size,
+            rows,
+        }
+    }
+
+    fn paths(estimates: &[FileEstimate]) -> Vec<&str> {
+        estimates.iter().map(|estimate| estimate.path.as_str()).collect()
+    }
+
+    #[test]
+    fn as_is_keeps_order() {
+        let mut estimates = vec![estimate("b", 20, None), estimate("a", 10, None)];
+        sort_estimates(QueueOrder::AsIs, &mut estimates);
+        assert_eq!(paths(&estimates), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn sorts_by_rows_when_all_counted() {
+        let mut estimates = vec![
+            estimate("large-few-rows", 900, Some(5)),
+            estimate("small-many-rows", 100, Some(50)),
+            estimate("medium", 500, Some(20)),
+        ];
+        sort_estimates(QueueOrder::SmallestFirst, &mut estimates);
+        assert_eq!(paths(&estimates), vec!["large-few-rows", "medium", "small-many-rows"]);
+        sort_estimates(QueueOrder::LargestFirst, &mut estimates);
+        assert_eq!(paths(&estimates), vec!["small-many-rows", "medium", "large-few-rows"]);
+    }
+
+    #[test]
+    fn falls_back_to_size_when_rows_missing() {
+        let mut estimates = vec![
+            estimate("big.xlsx", 900, Some(5)),
+            estimate("big.csv", 800, None),
+            estimate("small.xlsx", 100, Some(50)),
+        ];
+        sort_estimates(QueueOrder::SmallestFirst, &mut estimates);
+        assert_eq!(paths(&estimates), vec!["small.xlsx", "big.csv", "big.xlsx"]);
+    }
+
+    #[test]
+    fn equal_cost_keeps_original_order() {
+        let mut estimates = vec![estimate("first", 10, None), estimate("second", 10, None)];
+        sort_estimates(QueueOrder::LargestFirst, &mut estimates);
+        assert_eq!(paths(&estimates), vec!["first", "second"]);
+    }
+}
@@ -0,0 +1,801 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use calamine::{open_workbook_auto, Data, Range, Reader, Sheets};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::i18n::tr;
+use crate::scan::FileKind;
+
+// 预览时最多返回的行数
+pub const MAX_PREVIEW_ROWS: usize = 500;
+
+// read_cell_range 最多返回的单元格数
+pub const MAX_RANGE_CELLS: usize = 100_000;
+
+// Python脚本默认读取的循环数据工作表
+pub const DEFAULT_CYCLE_SHEET: &str = "Cycle";
+
+// Python脚本读取循环数据时必需的列（见 modules/file_parser.py）
+pub const REQUIRED_CYCLE_COLUMNS: &[&str] = &[
+    "充电比容量(mAh/g)",
+    "放电比容量(mAh/g)",
+    "放电中值电压(V)",
+    "充电比能量(mWh/g)",
+    "放电比能量(mWh/g)",
+];
+
+// 工作表预览结果
+#[derive(Debug, Serialize)]
+pub struct ExcelPreview {
+    pub sheet: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    // 工作表数据行总数（不含表头）
+    pub total_rows: usize,
+    pub truncated: bool,
+}
+
+// 必需列检查项
+#[derive(Debug, Serialize)]
+pub struct ColumnCheck {
+    pub name: String,
+    pub present: bool,
+}
+
+// 数据文件格式检查结果
+#[derive(Debug, Serialize)]
+pub struct BatteryFileCheck {
+    pub sheet: String,
+    // 数据行数（不含表头）
+    pub row_count: usize,
+    pub headers: Vec<String>,
+    pub required: Vec<ColumnCheck>,
+    pub missing: Vec<String>,
+    // 所有必需列都存在且至少有一行数据
+    pub valid: bool,
+}
+
+pub fn open_workbook(path: &str) -> Result<Sheets<BufReader<File>>, AppError> {
+    if !Path::new(path).is_file() {
+        return Err(AppError::FileNotFound(path.to_string()));
+    }
+    open_workbook_auto(path).map_err(|e| AppError::InvalidWorkbook {
+        path: path.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+// 读取指定工作表，未指定时使用第一个工作表，返回工作表名和数据区域
+pub fn read_sheet(
+    workbook: &mut Sheets<BufReader<File>>,
+    path: &str,
+    sheet: Option<&str>,
+) -> Result<(String, Range<Data>), AppError> {
+    let sheet_name = sheet_name(workbook, path, sheet)?;
+    let range = workbook
+        .worksheet_range(&sheet_name)
+        .map_err(|e| AppError::InvalidWorkbook {
+            path: path.to_string(),
+            reason: e.to_string(),
+        })?;
+    Ok((sheet_name, range))
+}
+
+// 确认工作表存在，未指定时返回第一个工作表的名称
+fn sheet_name(
+    workbook: &Sheets<BufReader<File>>,
+    path: &str,
+    sheet: Option<&str>,
+) -> Result<String, AppError> {
+    let sheet_name = match sheet.filter(|s| !s.is_empty()) {
+        Some(name) => name.to_string(),
+        None => workbook
+            .sheet_names()
+            .first()
+            .cloned()
+            .ok_or_else(|| AppError::InvalidWorkbook {
+                path: path.to_string(),
+                reason: tr!("工作簿中没有工作表", "The workbook has no worksheets"),
+            })?,
+    };
+
+    if !workbook.sheet_names().contains(&sheet_name) {
+        return Err(AppError::SheetNotFound(sheet_name));
+    }
+    Ok(sheet_name)
+}
+
+// 只读取工作表的表头（第一个非空行）。xlsx逐个单元格流式读取，读到第二行即停止，不加载整个工作表
+pub fn read_headers(path: &str, sheet: Option<&str>) -> Result<Vec<String>, AppError> {
+    let mut workbook = open_workbook(path)?;
+    let sheet_name = sheet_name(&workbook, path, sheet)?;
+    let invalid = |reason: String| AppError::InvalidWorkbook { path: path.to_string(), reason };
+
+    let Sheets::Xlsx(xlsx) = &mut workbook else {
+        let range = workbook.worksheet_range(&sheet_name).map_err(|e| invalid(e.to_string()))?;
+        return Ok(range
+            .rows()
+            .next()
+            .map(|row| row.iter().map(|cell| cell_to_string(cell).trim().to_string()).collect())
+            .unwrap_or_default());
+    };
+
+    let mut reader = xlsx.worksheet_cells_reader(&sheet_name).map_err(|e| invalid(e.to_string()))?;
+    let mut header_row = None;
+    let mut headers: Vec<(u32, String)> = Vec::new();
+    while let Some(cell) = reader.next_cell().map_err(|e| invalid(e.to_string()))? {
+        let (row, column) = cell.get_position();
+        let value = cell_to_string(&Data::from(cell.get_value().clone())).trim().to_string();
+        match header_row {
+            Some(header_row) if row > header_row => break,
+            // 跳过表头之前的空单元格
+            None if value.is_empty() => continue,
+            _ => {}
+        }
+        header_row = Some(row);
+        headers.push((column, value));
+    }
+
+    // 与 read_sheet 得到的表头一致：中间的空列保留为空字符串
+    let first = headers.first().map_or(0, |(column, _)| *column);
+    let mut row = Vec::new();
+    for (column, value) in headers {
+        let index = column.saturating_sub(first) as usize;
+        if index > row.len() {
+            row.resize(index, String::new());
+        }
+        row.push(value);
+    }
+    Ok(row)
+}
+
+// 按工作簿中的顺序列出所有工作表名称
+pub fn list_sheets(path: &str) -> Result<Vec<String>, AppError> {
+    Ok(open_workbook(path)?.sheet_names())
+}
+
+pub fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        other => other.to_string(),
+    }
+}
+
+// 读取工作表的表头和前 rows 行数据（最多 MAX_PREVIEW_ROWS 行）
+pub fn preview(path: &str, sheet: Option<&str>, rows: usize) -> Result<ExcelPreview, AppError> {
+    let mut workbook = open_workbook(path)?;
+    let (sheet, range) = read_sheet(&mut workbook, path, sheet)?;
+
+    let limit = rows.min(MAX_PREVIEW_ROWS);
+    let mut iter = range.rows();
+    let headers = iter
+        .next()
+        .map(|row| row.iter().map(cell_to_string).collect())
+        .unwrap_or_default();
+    let total_rows = range.height().saturating_sub(1);
+    let rows = iter
+        .take(limit)
+        .map(|row| row.iter().map(cell_to_string).collect())
+        .collect();
+
+    Ok(ExcelPreview {
+        sheet,
+        headers,
+        rows,
+        total_rows,
+        truncated: total_rows > limit,
+    })
+}
+
+// 检查数据文件是否包含Python脚本所需的列，sheet 未指定时检查 Cycle 工作表
+pub fn validate_battery_file(path: &str, sheet: Option<&str>) -> Result<BatteryFileCheck, AppError> {
+    // 空文件或过小的文件无法作为工作簿打开，单独报告以便与损坏的文件区分
+    let too_small = fs::metadata(path).is_ok_and(|metadata| {
+        FileKind::from_path(Path::new(path))
+            .is_some_and(|kind| metadata.len() < kind.min_valid_size())
+    });
+    if too_small {
+        return Err(AppError::InvalidInputFiles(vec![path.to_string()]));
+    }
+    let mut workbook = open_workbook(path)?;
+    let sheet = sheet.filter(|s| !s.is_empty()).unwrap_or(DEFAULT_CYCLE_SHEET);
+    let (sheet, range) = read_sheet(&mut workbook, path, Some(sheet))?;
+
+    let headers: Vec<String> = range
+        .rows()
+        .next()
+        .map(|row| row.iter().map(|cell| cell_to_string(cell).trim().to_string()).collect())
+        .unwrap_or_default();
+    let required: Vec<ColumnCheck> = REQUIRED_CYCLE_COLUMNS
+        .iter()
+        .map(|name| ColumnCheck {
+            name: name.to_string(),
+            present: headers.iter().any(|header| header == name),
+        })
+        .collect();
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|check| !check.present)
+        .map(|check| check.name.clone())
+        .collect();
+    let row_count = range.height().saturating_sub(1);
+
+    Ok(BatteryFileCheck {
+        sheet,
+        row_count,
+        headers,
+        valid: missing.is_empty() && row_count > 0,
+        required,
+        missing,
+    })
+}
+
+// 将工作簿（通常为旧版 .xls）转换为 .xlsx，保留所有工作表名称和单元格值，返回输出路径。
+// output_path 未指定时写入同目录下的同名 .xlsx 文件，该文件已存在时改用 "名称 (2).xlsx" 等不冲突的名称
+pub fn convert_to_xlsx(path: &str, output_path: Option<&str>) -> Result<String, AppError> {
+    let explicit = output_path.filter(|p| !p.is_empty());
+    let output = match explicit {
+        Some(output) => Path::new(output).to_path_buf(),
+        None => Path::new(path).with_extension("xlsx"),
+    };
+    if output == Path::new(path) {
+        return Err(AppError::ConversionFailed {
+            path: output.to_string_lossy().to_string(),
+            reason: tr!("输出文件不能与源文件相同", "The output file cannot be the source file"),
+        });
+    }
+    let output = if explicit.is_some() { output } else { unused_path(&output) };
+    let output_str = output.to_string_lossy().to_string();
+
+    let mut source = open_workbook(path)?;
+    let conversion_failed = |e: XlsxError| AppError::ConversionFailed {
+        path: output_str.clone(),
+        reason: e.to_string(),
+    };
+
+    let mut workbook = Workbook::new();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+    for name in source.sheet_names() {
+        let (_, range) = read_sheet(&mut source, path, Some(&name))?;
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&name).map_err(conversion_failed)?;
+        write_range(worksheet, &range, &date_format).map_err(conversion_failed)?;
+    }
+
+    workbook.save(&output).map_err(conversion_failed)?;
+    Ok(output_str)
+}
+
+// 路径已存在时依次尝试 "名称 (2).扩展名"、"名称 (3).扩展名"，返回第一个不存在的路径
+fn unused_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    let mut n = 2;
+    loop {
+        let candidate = path.with_file_name(format!("{} ({}){}", stem, n, extension));
+        if !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+// 写入整个数据区域：区域可能不从A1开始，按原位置写入
+fn write_range(
+    worksheet: &mut Worksheet,
+    range: &Range<Data>,
+    date_format: &Format,
+) -> Result<(), XlsxError> {
+    let (start_row, start_col) = range.start().unwrap_or((0, 0));
+    for (row, col, cell) in range.cells() {
+        let row = start_row + row as u32;
+        let col = (start_col as usize + col) as u16;
+        write_cell(worksheet, row, col, cell, date_format)?;
+    }
+    Ok(())
+}
+
+// 按原类型写入单元格，日期时间使用 date_format 显示
+fn write_cell(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    cell: &Data,
+    date_format: &Format,
+) -> Result<(), XlsxError> {
+    match cell {
+        Data::Empty => return Ok(()),
+        Data::Int(value) => worksheet.write_number(row, col, *value as f64),
+        Data::Float(value) => worksheet.write_number(row, col, *value),
+        Data::Bool(value) => worksheet.write_boolean(row, col, *value),
+        Data::DateTime(value) => {
+            worksheet.write_number_with_format(row, col, value.as_f64(), date_format)
+        }
+        Data::String(value) | Data::DateTimeIso(value) | Data::DurationIso(value) => {
+            worksheet.write_string(row, col, value)
+        }
+        Data::Error(value) => worksheet.write_string(row, col, value.to_string()),
+    }?;
+    Ok(())
+}
+
+// 合并方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum MergeMode {
+    // 每个输入文件一个工作表，以文件名命名
+    SeparateSheets,
+    // 所有文件的数据行依次追加到一个工作表，要求表头相同
+    AppendRows,
+}
+
+// 合并结果，warnings 列出被跳过的文件及原因
+#[derive(Debug, Serialize)]
+pub struct MergeResult {
+    pub output_path: String,
+    pub merged: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+// Excel工作表名最多31个字符
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+// 将多个数据文件的工作表（sheet 未指定时为各自的第一个工作表）合并为一个 .xlsx。
+// 无法读取或表头不一致（AppendRows）的文件跳过并记入 warnings，没有可合并的文件时返回错误
+pub fn merge_excel(
+    paths: &[String],
+    output_path: &str,
+    mode: MergeMode,
+    sheet: Option<&str>,
+) -> Result<MergeResult, AppError> {
+    let merge_failed = |reason: String| AppError::MergeFailed {
+        path: output_path.to_string(),
+        reason,
+    };
+    let output = Path::new(output_path);
+    if paths.iter().any(|path| Path::new(path) == output) {
+        return Err(merge_failed(tr!(
+            "输出文件不能是输入文件之一",
+            "The output file cannot be one of the inputs"
+        )));
+    }
+
+    let mut workbook = Workbook::new();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+    let mut merged = Vec::new();
+    let mut warnings = Vec::new();
+    // AppendRows：第一个成功读取的文件的表头，以及下一行的写入位置
+    let mut header: Option<Vec<String>> = None;
+    let mut next_row = 0u32;
+    let mut sheet_names: Vec<String> = Vec::new();
+    if mode == MergeMode::AppendRows {
+        workbook.add_worksheet();
+    }
+
+    for path in paths {
+        let read = open_workbook(path).and_then(|mut source| read_sheet(&mut source, path, sheet));
+        let range = match read {
+            Ok((_, range)) => range,
+            Err(e) => {
+                warnings.push(tr!("已跳过 {}: {}", "Skipped {}: {}", path, e));
+                continue;
+            }
+        };
+
+        match mode {
+            MergeMode::SeparateSheets => {
+                let name = unique_sheet_name(path, &sheet_names);
+                let worksheet = workbook.add_worksheet();
+                worksheet.set_name(&name).map_err(|e| merge_failed(e.to_string()))?;
+                write_range(worksheet, &range, &date_format).map_err(|e| merge_failed(e.to_string()))?;
+                sheet_names.push(name);
+            }
+            MergeMode::AppendRows => {
+                let mut rows = range.rows();
+                let file_header = rows.next().map(header_of).unwrap_or_default();
+                if file_header.is_empty() {
+                    warnings.push(tr!("已跳过 {}: 工作表为空", "Skipped {}: the sheet is empty", path));
+                    continue;
+                }
+                let worksheet = workbook
+                    .worksheet_from_index(0)
+                    .map_err(|e| merge_failed(e.to_string()))?;
+                if header.is_none() {
+                    for (col, title) in file_header.iter().enumerate() {
+                        worksheet
+                            .write_string(0, col as u16, title)
+                            .map_err(|e| merge_failed(e.to_string()))?;
+                    }
+                    header = Some(file_header);
+                    next_row = 1;
+                } else if header.as_ref() != Some(&file_header) {
+                    warnings.push(tr!(
+                        "已跳过 {}: 表头与第一个文件不一致",
+                        "Skipped {}: the header differs from the first file",
+                        path
+                    ));
+                    continue;
+                }
+
+                for row in rows {
+                    for (col, cell) in row.iter().enumerate() {
+                        write_cell(worksheet, next_row, col as u16, cell, &date_format)
+                            .map_err(|e| merge_failed(e.to_string()))?;
+                    }
+                    next_row += 1;
+                }
+            }
+        }
+        merged.push(path.clone());
+    }
+
+    if merged.is_empty() {
+        return Err(merge_failed(tr!("没有可合并的文件", "No files could be merged")));
+    }
+    workbook.save(output).map_err(|e| merge_failed(e.to_string()))?;
+    Ok(MergeResult {
+        output_path: output_path.to_string(),
+        merged,
+        warnings,
+    })
+}
+
+// 表头去掉末尾的空列，便于比较
+fn header_of(row: &[Data]) -> Vec<String> {
+    let mut header: Vec<String> = row
+        .iter()
+        .map(|cell| cell_to_string(cell).trim().to_string())
+        .collect();
+    while header.last().is_some_and(|title| title.is_empty()) {
+        header.pop();
+    }
+    header
+}
+
+// 以文件名（不含扩展名）作为工作表名：去掉Excel不允许的字符并截断到31个字符，
+// 重名时（不区分大小写）追加 " (2)"、" (3)"…
+fn unique_sheet_name(path: &str, existing: &[String]) -> String {
+    let stem = Path::new(path).file_stem().unwrap_or_default().to_string_lossy();
+    let cleaned: String = stem
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .collect();
+    let cleaned = cleaned.trim_matches('\'').trim();
+    let base = if cleaned.is_empty() { "Sheet" } else { cleaned };
+
+    let taken = |name: &str| {
+        existing
+            .iter()
+            .any(|other| other.to_lowercase() == name.to_lowercase())
+    };
+    let mut suffix = 1;
+    loop {
+        let tail = if suffix == 1 { String::new() } else { format!(" ({})", suffix) };
+        let keep = MAX_SHEET_NAME_CHARS - tail.chars().count();
+        let name: String = base.chars().take(keep).collect::<String>() + &tail;
+        if !taken(&name) {
+            return name;
+        }
+        suffix += 1;
+    }
+}
+
+// extract_series 最多报告的无法解析的单元格数
+const MAX_UNPARSED_CELLS: usize = 100;
+
+// 图表数据：x 与 series 中的每个数组等长，按行对应
+#[derive(Debug, Serialize)]
+pub struct SeriesData {
+    pub sheet: String,
+    pub x: Vec<f64>,
+    // 列名 -> 数值；空单元格或无法解析的值为 NaN（序列化为 null）
+    pub series: HashMap<String, Vec<f64>>,
+    // 无法解析为数字的非空单元格（最多 MAX_UNPARSED_CELLS 个）；x 无法解析的行整行跳过
+    pub unparsed: Vec<UnparsedCell>,
+    pub unparsed_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnparsedCell {
+    // Excel中的行号（从1开始）
+    pub row: usize,
+    pub column: String,
+    pub value: String,
+}
+
+// 读取 x_column 和 y_columns 列的数值，用于前端直接绘图（如放电容量-循环次数曲线）。
+// 文本单元格去除空白后按数字解析，x 为空的行跳过
+pub fn extract_series(
+    path: &str,
+    sheet: Option<&str>,
+    x_column: &str,
+    y_columns: &[String],
+) -> Result<SeriesData, AppError> {
+    let mut workbook = open_workbook(path)?;
+    let (sheet, range) = read_sheet(&mut workbook, path, sheet)?;
+
+    let mut rows = range.rows();
+    let headers: Vec<String> = rows
+        .next()
+        .map(|row| row.iter().map(|cell| cell_to_string(cell).trim().to_string()).collect())
+        .unwrap_or_default();
+    let position = |name: &str| headers.iter().position(|header| header == name.trim());
+    let missing: Vec<String> = std::iter::once(x_column)
+        .chain(y_columns.iter().map(String::as_str))
+        .filter(|name| position(name).is_none())
+        .map(|name| name.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::ColumnsNotFound { sheet, columns: missing });
+    }
+    let x_index = position(x_column).unwrap_or_default();
+    // 重复的列名只取一次，保证每个数组与 x 等长
+    let mut seen = HashSet::new();
+    let y_indexes: Vec<(&String, usize)> = y_columns
+        .iter()
+        .filter(|name| seen.insert(name.as_str()))
+        .map(|name| (name, position(name).unwrap_or_default()))
+        .collect();
+
+    let mut data = SeriesData {
+        sheet,
+        x: Vec::new(),
+        series: y_columns.iter().map(|name| (name.clone(), Vec::new())).collect(),
+        unparsed: Vec::new(),
+        unparsed_count: 0,
+    };
+    // 数据区域不一定从A1开始，行号按区域起点换算
+    let first_row = range.start().map_or(0, |(row, _)| row as usize) + 2;
+    for (i, row) in rows.enumerate() {
+        let cell = |index: usize| row.get(index).unwrap_or(&Data::Empty);
+        let x = match parse_number(cell(x_index)) {
+            Ok(Some(x)) => x,
+            Ok(None) => continue,
+            Err(value) => {
+                data.unparsed(first_row + i, x_column, value);
+                continue;
+            }
+        };
+        data.x.push(x);
+        for &(name, index) in &y_indexes {
+            let y = parse_number(cell(index)).unwrap_or_else(|value| {
+                data.unparsed(first_row + i, name, value);
+                None
+            });
+            if let Some(values) = data.series.get_mut(name) {
+                values.push(y.unwrap_or(f64::NAN));
+            }
+        }
+    }
+    Ok(data)
+}
+
+impl SeriesData {
+    fn unparsed(&mut self, row: usize, column: &str, value: String) {
+        self.unparsed_count += 1;
+        if self.unparsed.len() < MAX_UNPARSED_CELLS {
+            self.unparsed.push(UnparsedCell { row, column: column.to_string(), value });
+        }
+    }
+}
+
+// 空单元格返回 Ok(None)，无法解析时返回单元格文本
+fn parse_number(cell: &Data) -> Result<Option<f64>, String> {
+    match cell {
+        Data::Empty => Ok(None),
+        Data::Int(value) => Ok(Some(*value as f64)),
+        Data::Float(value) => Ok(Some(*value)),
+        Data::Bool(value) => Ok(Some(if *value { 1.0 } else { 0.0 })),
+        Data::String(text) if text.trim().is_empty() => Ok(None),
+        Data::String(text) => text.trim().parse().map(Some).map_err(|_| text.clone()),
+        other => Err(cell_to_string(other)),
+    }
+}
+
+// 读取A1格式区域（如 "A1:D20" 或单个单元格 "B3"）内的单元格值，超出数据区域的单元格为空字符串
+pub fn read_cell_range(path: &str, sheet: Option<&str>, range: &str) -> Result<Vec<Vec<String>>, AppError> {
+    let ((first_row, first_col), (last_row, last_col)) = parse_range(range)?;
+    let cells = (last_row - first_row + 1) as usize * (last_col - first_col + 1) as usize;
+    if cells > MAX_RANGE_CELLS {
+        return Err(AppError::InvalidRange {
+            range: range.to_string(),
+            reason: tr!(
+                "区域包含 {} 个单元格，超过上限 {}",
+                "The range contains {} cells, more than the limit of {}",
+                cells,
+                MAX_RANGE_CELLS
+            ),
+        });
+    }
+
+    let mut workbook = open_workbook(path)?;
+    let (_, data) = read_sheet(&mut workbook, path, sheet)?;
+    Ok((first_row..=last_row)
+        .map(|row| {
+            (first_col..=last_col)
+                .map(|col| data.get_value((row, col)).map(cell_to_string).unwrap_or_default())
+                .collect()
+        })
+        .collect())
+}
+
+// 从0开始的 (行, 列) 位置
+type CellPos = (u32, u32);
+
+// 解析A1格式区域，返回起止位置；起止顺序颠倒时自动交换
+fn parse_range(range: &str) -> Result<(CellPos, CellPos), AppError> {
+    let invalid = |reason: String| AppError::InvalidRange {
+        range: range.to_string(),
+        reason,
+    };
+    let text = range.trim().replace('$', "");
+    let (start, end) = text.split_once(':').unwrap_or((&text, &text));
+    let start = parse_cell(start)
+        .ok_or_else(|| invalid(tr!("无法解析起始单元格", "Cannot parse the start cell")))?;
+    let end = parse_cell(end)
+        .ok_or_else(|| invalid(tr!("无法解析结束单元格", "Cannot parse the end cell")))?;
+    Ok((
+        (start.0.min(end.0), start.1.min(end.1)),
+        (start.0.max(end.0), start.1.max(end.1)),
+    ))
+}
+
+// 解析单个单元格引用（如 "AB12"），列字母不区分大小写
+fn parse_cell(cell: &str) -> Option<CellPos> {
+    let cell = cell.trim();
+    let split = cell.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, digits) = cell.split_at(split);
+    // Excel最多 XFD 列（3个字母）、1048576 行
+    if letters.is_empty() || letters.len() > 3 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let col = letters
+        .bytes()
+        .fold(0u32, |acc, b| acc * 26 + u32::from(b.to_ascii_uppercase() - b'A' + 1));
+    let row: u32 = digits.parse().ok()?;
+    if row == 0 || row > 1_048_576 || col > 16_384 {
+        return None;
+    }
+    Some((row - 1, col - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 在 (start_row, start_col) 处写入一个只有表头和数据行的工作簿
+    fn write_workbook(path: &Path, start: CellPos, header: &[&str], rows: &[&[f64]]) {
+        let (start_row, start_col) = start;
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        for (col, title) in header.iter().enumerate() {
+            worksheet.write_string(start_row, start_col as u16 + col as u16, *title).unwrap();
+        }
+        for (index, row) in rows.iter().enumerate() {
+            for (col, value) in row.iter().enumerate() {
+                let row = start_row + 1 + index as u32;
+                worksheet.write_number(row, start_col as u16 + col as u16, *value).unwrap();
+            }
+        }
+        workbook.save(path).unwrap();
+    }
+
+    fn path_string(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    fn sheet(path: &str, name: Option<&str>) -> Range<Data> {
+        let mut workbook = open_workbook(path).unwrap();
+        read_sheet(&mut workbook, path, name).unwrap().1
+    }
+
+    #[test]
+    fn separate_sheets_keep_cell_positions() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("A1.xlsx");
+        let second = dir.path().join("A2.xlsx");
+        write_workbook(&first, (2, 1), &["通道", "首放"], &[&[1.0, 180.5]]);
+        write_workbook(&second, (0, 0), &["通道", "首放"], &[&[2.0, 175.0]]);
+        let output = path_string(&dir.path().join("merged.xlsx"));
+
+        let result = merge_excel(
+            &[path_string(&first), path_string(&second)],
+            &output,
+            MergeMode::SeparateSheets,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.merged.len(), 2);
+        assert!(result.warnings.is_empty());
+
+        let range = sheet(&output, Some("A1"));
+        assert_eq!(range.start(), Some((2, 1)));
+        assert_eq!(range.get_value((2, 1)), Some(&Data::String("通道".to_string())));
+        assert_eq!(range.get_value((3, 2)), Some(&Data::Float(180.5)));
+        let range = sheet(&output, Some("A2"));
+        assert_eq!(range.start(), Some((0, 0)));
+        assert_eq!(range.get_value((1, 1)), Some(&Data::Float(175.0)));
+    }
+
+    #[test]
+    fn append_rows_skips_mismatched_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("A1.xlsx");
+        let second = dir.path().join("A2.xlsx");
+        let other = dir.path().join("B1.xlsx");
+        write_workbook(&first, (0, 0), &["通道", "首放"], &[&[1.0, 180.5], &[2.0, 181.0]]);
+        write_workbook(&second, (0, 0), &["通道", "首放"], &[&[3.0, 175.0]]);
+        write_workbook(&other, (0, 0), &["通道", "首效"], &[&[4.0, 88.0]]);
+        let paths = [path_string(&first), path_string(&other), path_string(&second)];
+        let output = path_string(&dir.path().join("merged.xlsx"));
+
+        let result = merge_excel(&paths, &output, MergeMode::AppendRows, None).unwrap();
+        assert_eq!(result.merged, vec![paths[0].clone(), paths[2].clone()]);
+        assert_eq!(result.warnings.len(), 1);
+
+        let range = sheet(&output, None);
+        assert_eq!(range.height(), 4);
+        let channels: Vec<Data> = range.rows().skip(1).map(|row| row[0].clone()).collect();
+        assert_eq!(channels, vec![Data::Float(1.0), Data::Float(2.0), Data::Float(3.0)]);
+    }
+
+    #[test]
+    fn merge_rejects_input_as_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = path_string(&dir.path().join("A1.xlsx"));
+        let result = merge_excel(std::slice::from_ref(&input), &input, MergeMode::SeparateSheets, None);
+        assert!(matches!(result, Err(AppError::MergeFailed { .. })));
+    }
+
+    #[test]
+    fn merge_fails_without_readable_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = path_string(&dir.path().join("missing.xlsx"));
+        let output = path_string(&dir.path().join("merged.xlsx"));
+        let result = merge_excel(&[missing], &output, MergeMode::AppendRows, None);
+        assert!(matches!(result, Err(AppError::MergeFailed { .. })));
+        assert!(!Path::new(&output).exists());
+    }
+
+    #[test]
+    fn convert_keeps_existing_xlsx() {
+        let dir = tempfile::tempdir().unwrap();
+        // .xlsm 按 xlsx 格式读取，转换的默认输出为同名 .xlsx
+        let source = dir.path().join("A1.xlsm");
+        write_workbook(&source, (0, 0), &["通道", "首放"], &[&[1.0, 180.5]]);
+        let existing = dir.path().join("A1.xlsx");
+        fs::write(&existing, b"existing").unwrap();
+
+        let output = convert_to_xlsx(&path_string(&source), None).unwrap();
+        assert_eq!(output, path_string(&dir.path().join("A1 (2).xlsx")));
+        assert_eq!(fs::read(&existing).unwrap(), b"existing");
+        assert_eq!(sheet(&output, None).get_value((1, 1)), Some(&Data::Float(180.5)));
+    }
+
+    #[test]
+    fn sheet_names_are_cleaned_and_unique() {
+        assert_eq!(unique_sheet_name("/data/A1[2].xlsx", &[]), "A12");
+        assert_eq!(unique_sheet_name("/data/a1.xlsx", &["A1".to_string()]), "a1 (2)");
+        assert_eq!(
+            unique_sheet_name("/data/a1.xlsx", &["A1".to_string(), "a1 (2)".to_string()]),
+            "a1 (3)"
+        );
+        let long = unique_sheet_name(&format!("/data/{}.xlsx", "x".repeat(40)), &[]);
+        assert_eq!(long.chars().count(), MAX_SHEET_NAME_CHARS);
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("B2:C5").unwrap(), ((1, 1), (4, 2)));
+        // 起止顺序颠倒时自动交换，忽略 $
+        assert_eq!(parse_range("$C$5:$B$2").unwrap(), ((1, 1), (4, 2)));
+        assert!(matches!(parse_range("B2:?"), Err(AppError::InvalidRange { .. })));
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::i18n::tr;
+
+// 单个文件的删除结果
+#[derive(Debug, Serialize)]
+pub struct DeleteOutcome {
+    pub path: String,
+    pub deleted: bool,
+    // 失败原因，成功时为 None
+    pub error: Option<String>,
+}
+
+// 删除 root 文件夹（含子文件夹）中的文件：to_trash 为 true 时移到回收站，否则永久删除。
+// 不删除文件夹，也不删除 root 之外的路径；各文件分别返回结果，单个失败不影响其余文件
+pub fn delete_files(root: &str, paths: &[String], to_trash: bool) -> Result<Vec<DeleteOutcome>, AppError> {
+    let root_dir = root_dir(root)?;
+    Ok(paths
+        .iter()
+        .map(|path| {
+            let result = checked_path(&root_dir, path).and_then(|target| {
+                if to_trash {
+                    trash::delete(&target).map_err(|e| e.to_string())
+                } else {
+                    fs::remove_file(&target).map_err(|e| e.to_string())
+                }
+            });
+            DeleteOutcome {
+                path: path.clone(),
+                deleted: result.is_ok(),
+                error: result.err(),
+            }
+        })
+        .collect())
+}
+
+// 一次重命名操作，to 可以位于 root 的其他子文件夹中
+#[derive(Debug, Deserialize)]
+pub struct RenameOp {
+    pub from: String,
+    pub to: String,
+}
+
+// 单个重命名操作的结果
+#[derive(Debug, Serialize)]
+pub struct RenameOutcome {
+    pub from: String,
+    pub to: String,
+    pub renamed: bool,
+    // 失败原因；因其他操作失败而回滚或未执行时也在此说明
+    pub error: Option<String>,
+}
+
+// 批量重命名 root 中的文件：先校验全部操作（源文件存在、目标不存在且互不冲突、都在 root 中），
+// 有任何一项不通过时不执行任何重命名；执行中途失败时按相反顺序撤销已完成的重命名
+pub fn rename_files(root: &str, ops: &[RenameOp]) -> Result<Vec<RenameOutcome>, AppError> {
+    let root_dir = root_dir(root)?;
+    let mut outcomes: Vec<RenameOutcome> = ops
+        .iter()
+        .map(|op| RenameOutcome {
+            from: op.from.clone(),
+            to: op.to.clone(),
+            renamed: false,
+            error: None,
+        })
+        .collect();
+
+    let mut sources = HashSet::new();
+    let mut targets = HashSet::new();
+    let mut resolved = Vec::new();
+    for (op, outcome) in ops.iter().zip(outcomes.iter_mut()) {
+        let checked = check_rename(&root_dir, op).and_then(|(from, to)| {
+            if !sources.insert(from.clone()) {
+                return Err(tr!("源文件重复: {}", "Duplicate source file: {}", op.from));
+            }
+            if !targets.insert(to.clone()) {
+                return Err(tr!("目标文件名重复: {}", "Duplicate target name: {}", op.to));
+            }
+            Ok((from, to))
+        });
+        match checked {
+            Ok(paths) => resolved.push(paths),
+            Err(e) => outcome.error = Some(e),
+        }
+    }
+    if outcomes.iter().any(|outcome| outcome.error.is_some()) {
+        for outcome in outcomes.iter_mut().filter(|outcome| outcome.error.is_none()) {
+            outcome.error = Some(tr!(
+                "其他操作校验失败，未执行",
+                "Not performed because another operation failed validation"
+            ));
+        }
+        return Ok(outcomes);
+    }
+
+    for (i, (from, to)) in resolved.iter().enumerate() {
+        if let Err(e) = fs::rename(from, to) {
+            outcomes[i].error = Some(e.to_string());
+            // 撤销已完成的重命名，撤销失败时保留为已重命名并注明原因
+            for (j, (done_from, done_to)) in resolved[..i].iter().enumerate().rev() {
+                outcomes[j].error = Some(match fs::rename(done_to, done_from) {
+                    Ok(()) => {
+                        outcomes[j].renamed = false;
+                        tr!("后续操作失败，已撤销", "Rolled back because a later operation failed")
+                    }
+                    Err(e) => tr!(
+                        "后续操作失败，撤销时出错: {}",
+                        "A later operation failed and rollback failed: {}",
+                        e
+                    ),
+                });
+            }
+            for outcome in &mut outcomes[i + 1..] {
+                outcome.error = Some(tr!(
+                    "前面的操作失败，未执行",
+                    "Not performed because an earlier operation failed"
+                ));
+            }
+            return Ok(outcomes);
+        }
+        outcomes[i].renamed = true;
+    }
+    Ok(outcomes)
+}
+
+// 校验单个重命名操作，返回规范化后的源路径和目标路径
+fn check_rename(root: &Path, op: &RenameOp) -> Result<(PathBuf, PathBuf), String> {
+    let metadata = fs::symlink_metadata(&op.from)
+        .map_err(|_| tr!("文件不存在: {}", "File not found: {}", op.from))?;
+    if metadata.is_dir() {
+        return Err(tr!("只能重命名文件: {}", "Only files can be renamed: {}", op.from));
+    }
+    let from = resolve_in_root(root, &op.from)?;
+    let to = resolve_in_root(root, &op.to)?;
+    if from == to {
+        return Err(tr!("新旧文件名相同: {}", "Source and target are the same: {}", op.to));
+    }
+    // 仅大小写不同的重命名在不区分大小写的文件系统上目标“已存在”，此时仍允许
+    let case_only = from.to_string_lossy().to_lowercase() == to.to_string_lossy().to_lowercase();
+    if !case_only && fs::symlink_metadata(&to).is_ok() {
+        return Err(tr!("目标文件已存在: {}", "Target already exists: {}", op.to));
+    }
+    Ok((from, to))
+}
+
+fn root_dir(root: &str) -> Result<PathBuf, AppError> {
+    Path::new(root)
+        .canonicalize()
+        .ok()
+        .filter(|dir| dir.is_dir())
+        .ok_or_else(|| AppError::DirectoryNotFound(root.to_string()))
+}
+
+// 校验路径是 root 中已存在的文件（或符号链接本身），返回规范化后的路径
+fn checked_path(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let metadata = fs::symlink_metadata(path)
+        .map_err(|_| tr!("文件不存在: {}", "File not found: {}", path))?;
+    if metadata.is_dir() {
+        return Err(tr!("不能删除文件夹: {}", "Refusing to delete a folder: {}", path));
+    }
+    resolve_in_root(root, path)
+}
+
+// 规范化所在目录后拼接文件名（不要求文件存在，符号链接指向链接本身而不是链接目标），
+// 结果不在 root 中时返回错误
+fn resolve_in_root(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let target = Path::new(path);
+    let (Some(parent), Some(name)) = (target.parent(), target.file_name()) else {
+        return Err(tr!("无效的文件路径: {}", "Invalid file path: {}", path));
+    };
+    let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+    let resolved = parent
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .join(name);
+    if !resolved.starts_with(root) {
+        return Err(tr!(
+            "文件不在允许的文件夹中: {}",
+            "File is outside the allowed folder: {}",
+            path
+        ));
+    }
+    Ok(resolved)
+}
@@ -0,0 +1,98 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::Serialize;
+
+// 错误信息使用的语言，默认中文
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    Zh,
+    En,
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+impl Locale {
+    // 接受 "zh"、"zh-CN"、"en"、"en_US" 等写法，不支持的语言返回 None
+    pub fn parse(lang: &str) -> Option<Locale> {
+        let primary = lang.trim().split(['-', '_']).next().unwrap_or_default();
+        match primary.to_ascii_lowercase().as_str() {
+            "zh" => Some(Locale::Zh),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+}
+
+pub fn current() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::Zh,
+    }
+}
+
+pub fn set(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+// 按当前语言选择中文或英文格式串：tr!("文件不存在: {}", "File not found: {}", path)
+macro_rules! tr {
+    ($zh:literal, $en:literal $(, $arg:expr)* $(,)?) => {
+        match $crate::i18n::current() {
+            $crate::i18n::Locale::Zh => format!($zh $(, $arg)*),
+            $crate::i18n::Locale::En => format!($en $(, $arg)*),
+        }
+    };
+}
+pub(crate) use tr;
+
+// IO错误上下文的英文对照，未收录的上下文原样显示
+const IO_CONTEXTS: &[(&str, &str)] = &[
+    ("读取文件夹失败", "Failed to read folder"),
+    ("读取文件元数据失败", "Failed to read file metadata"),
+    ("创建输出文件夹失败", "Failed to create output folder"),
+    ("等待Python脚本结束失败", "Failed to wait for the Python script"),
+    ("序列化配置失败", "Failed to serialize config"),
+    ("创建临时配置文件失败", "Failed to create temporary config file"),
+    ("写入临时配置文件失败", "Failed to write temporary config file"),
+    ("创建临时目录失败", "Failed to create temporary folder"),
+    ("创建预设目录失败", "Failed to create presets folder"),
+    ("序列化预设失败", "Failed to serialize preset"),
+    ("保存预设失败", "Failed to save preset"),
+    ("读取预设失败", "Failed to read preset"),
+    ("解析预设失败", "Failed to parse preset"),
+    ("读取预设目录失败", "Failed to read presets folder"),
+    ("删除预设失败", "Failed to delete preset"),
+    ("创建配置目录失败", "Failed to create config folder"),
+    ("读取最近使用的文件夹失败", "Failed to read recent folders"),
+    ("序列化最近使用的文件夹失败", "Failed to serialize recent folders"),
+    ("保存最近使用的文件夹失败", "Failed to save recent folders"),
+    ("清除最近使用的文件夹失败", "Failed to clear recent folders"),
+    ("创建日志目录失败", "Failed to create log folder"),
+    ("打开运行日志失败", "Failed to open run log"),
+    ("写入运行日志失败", "Failed to write run log"),
+    ("写入报告失败", "Failed to write report"),
+    ("创建自检临时目录失败", "Failed to create self-test temporary folder"),
+    ("读取日志文件失败", "Failed to read log file"),
+    ("创建应用目录失败", "Failed to create application folder"),
+    ("读取CSV文件失败", "Failed to read CSV file"),
+    ("读取运行记录失败", "Failed to read last run records"),
+    ("序列化运行记录失败", "Failed to serialize last run records"),
+    ("保存运行记录失败", "Failed to save last run records"),
+    ("序列化运行清单失败", "Failed to serialize run manifest"),
+    ("保存运行清单失败", "Failed to save run manifest"),
+    ("读取运行清单失败", "Failed to read run manifest"),
+    ("解析运行清单失败", "Failed to parse run manifest"),
+    ("读取运行摘要失败", "Failed to read run summary"),
+    ("解析运行摘要失败", "Failed to parse run summary"),
+];
+
+pub fn io_context(context: &str) -> &str {
+    match current() {
+        Locale::Zh => context,
+        Locale::En => IO_CONTEXTS
+            .iter()
+            .find(|(zh, _)| *zh == context)
+            .map_or(context, |(_, en)| en),
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::analysis::AnalysisError;
+use crate::output::OutputFile;
+
+// 默认保留最近多少个任务的输出
+const DEFAULT_LIMIT: usize = 20;
+// 每个输出流最多保留的字节数，超出时只保留末尾部分
+const MAX_STREAM_BYTES: usize = 256 * 1024;
+
+// 已结束任务的输出和结果，供事后查看失败原因
+#[derive(Debug, Clone, Serialize)]
+pub struct JobOutput {
+    pub job_id: u64,
+    pub input_folder: String,
+    // 结束时间（UTC，RFC-3339格式）
+    pub finished_at: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    // 输出超过 MAX_STREAM_BYTES 被截断（只保留末尾）
+    pub stdout_truncated: bool,
+    pub stderr_truncated: bool,
+    pub analysis_errors: Vec<AnalysisError>,
+    pub output_files: Vec<OutputFile>,
+    pub duration_ms: u64,
+    pub attempts: u32,
+    // 运行失败时的错误类别和信息
+    pub error_kind: Option<&'static str>,
+    pub error: Option<String>,
+}
+
+impl JobOutput {
+    // 截断过长的输出流
+    fn cap_streams(mut self) -> Self {
+        self.stdout_truncated = keep_tail(&mut self.stdout, MAX_STREAM_BYTES);
+        self.stderr_truncated = keep_tail(&mut self.stderr, MAX_STREAM_BYTES);
+        self
+    }
+}
+
+// 只保留最后 max 个字节（按字符边界向后对齐），返回是否截断
+fn keep_tail(text: &mut String, max: usize) -> bool {
+    if text.len() <= max {
+        return false;
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    text.drain(..start);
+    true
+}
+
+struct StoreState {
+    // 按结束顺序排列，最早的在前
+    outputs: VecDeque<JobOutput>,
+    limit: usize,
+}
+
+// 最近结束的任务输出，超过 limit 个时淘汰最早的
+pub struct JobOutputStore {
+    state: Mutex<StoreState>,
+}
+
+impl Default for JobOutputStore {
+    fn default() -> Self {
+        JobOutputStore {
+            state: Mutex::new(StoreState { outputs: VecDeque::new(), limit: DEFAULT_LIMIT }),
+        }
+    }
+}
+
+impl JobOutputStore {
+    pub fn record(&self, output: JobOutput) {
+        let mut state = self.state.lock().unwrap();
+        state.outputs.retain(|existing| existing.job_id != output.job_id);
+        state.outputs.push_back(output.cap_streams());
+        evict(&mut state);
+    }
+
+    pub fn get(&self, job_id: u64) -> Option<JobOutput> {
+        let state = self.state.lock().unwrap();
+        state.outputs.iter().find(|output| output.job_id == job_id).cloned()
+    }
+
+    // 设置保留的任务数（至少为1），调低时立即淘汰多余的记录
+    pub fn set_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = limit.max(1);
+        evict(&mut state);
+    }
+}
+
+fn evict(state: &mut StoreState) {
+    while state.outputs.len() > state.limit {
+        state.outputs.pop_front();
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppError;
+
+// 串行化读-改-写，避免并发任务互相覆盖记录
+static LOCK: Mutex<()> = Mutex::new(());
+
+// 各输入文件夹最近一次成功运行的开始时间（Unix时间戳，秒），保存在应用配置目录下的 last_runs.json 中
+fn last_runs_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::AppDirUnavailable(e.to_string()))?;
+    Ok(dir.join("last_runs.json"))
+}
+
+fn read_map(path: &PathBuf) -> Result<BTreeMap<String, u64>, AppError> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(AppError::io("读取运行记录失败", e)),
+    };
+    // 文件损坏时从空记录重新开始
+    Ok(serde_json::from_str(&json).unwrap_or_default())
+}
+
+// 文件夹最近一次成功运行的开始时间，从未成功运行时为 None
+pub fn get(app: &AppHandle, folder: &str) -> Result<Option<u64>, AppError> {
+    let _guard = LOCK.lock().unwrap();
+    Ok(read_map(&last_runs_path(app)?)?.get(folder).copied())
+}
+
+pub fn record(app: &AppHandle, folder: &str, started_unix: u64) -> Result<(), AppError> {
+    if folder.trim().is_empty() {
+        return Ok(());
+    }
+    let _guard = LOCK.lock().unwrap();
+    let path = last_runs_path(app)?;
+    let mut runs = read_map(&path)?;
+    runs.insert(folder.to_string(), started_unix);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| AppError::io("创建配置目录失败", e))?;
+    }
+    let json = serde_json::to_string_pretty(&runs)
+        .map_err(|e| AppError::io("序列化运行记录失败", io::Error::from(e)))?;
+    fs::write(&path, json).map_err(|e| AppError::io("保存运行记录失败", e))
+}
+
+// 记录失败不影响处理结果，只写日志
+pub fn record_quietly(app: &AppHandle, folder: &str, started_unix: u64) {
+    if let Err(e) = record(app, folder, started_unix) {
+        log::warn!("记录运行时间失败: {}", e);
+    }
+}
@@ -1,139 +1,621 @@
-use std::fs;
 use std::path::Path;
-use std::process::Command;
-use serde::{Deserialize, Serialize};
+use schemars::schema::RootSchema;
+use tauri::{AppHandle, Emitter, Manager, RunEvent, State};
 
-#[derive(Debug, Serialize, Deserialize)]
-struct FileInfo {
-    name: String,
+mod analysis;
+mod appdirs;
+mod batch;
+mod compare;
+mod config;
+mod consistency;
+mod csvfile;
+mod environment;
+mod estimate;
+mod error;
+mod excel;
+mod fileops;
+mod i18n;
+mod joboutput;
+mod lastrun;
+mod logtail;
+mod manifest;
+mod opener;
+mod output;
+mod paths;
+mod presets;
+mod process;
+mod progress;
+mod python;
+mod queue;
+mod recent;
+mod reference;
+mod resultdata;
+mod report;
+mod runner;
+mod runlog;
+mod scan;
+mod script;
+mod selftest;
+mod summary;
+mod tempfiles;
+mod vendor;
+mod watch;
+
+use appdirs::{AppDir, AppDirs};
+use compare::RunComparison;
+use config::{ConfigOptions, FieldError, ProcessConfig};
+use consistency::FolderConsistency;
+use csvfile::CsvPreview;
+use environment::{EnvironmentStatus, RuntimeInfo, VersionInfo};
+use estimate::FileEstimate;
+use error::AppError;
+use excel::{BatteryFileCheck, ExcelPreview, MergeMode, MergeResult, SeriesData};
+use fileops::{DeleteOutcome, RenameOp, RenameOutcome};
+use joboutput::{JobOutput, JobOutputStore};
+use logtail::LogTailRegistry;
+use manifest::RunManifest;
+use process::ProcessRegistry;
+use queue::{JobQueue, QueueInfo};
+use reference::ReferencePreview;
+use runner::{FilesOutcome, ProcessOutcome};
+use scan::{
+    DirectoryCache, DirectoryPage, DirectoryStats, DuplicateGroup, FileInfo, PathInfo, ScanOptions, SortBy,
+};
+use selftest::SelfTestReport;
+use summary::RunSummary;
+use vendor::FormatDetection;
+use watch::WatcherRegistry;
+
+// 在阻塞线程池中执行耗时操作，避免阻塞主线程
+async fn run_blocking<T, F>(task: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| AppError::TaskFailed(e.to_string()))?
+}
+
+// Tauri命令：读取目录文件（recursive 为 true 时递归扫描子目录，max_depth 限制层数；
+// 默认跳过Office临时/锁定文件和隐藏文件，include_temp 为 true 时包含）。
+// 结果按 sort_by 排序（默认按文件名）后按 offset/limit 分页返回；include_hash 为 true 时计算内容哈希，
+// check_locked 为 true 时检测Excel文件是否被占用（默认不检测，locked 为 false）。
+// follow_symlinks 为 true 时跟随符号链接（默认报告链接本身的信息）。
+// report_progress 为 true 时通过 battery://scan-progress 事件报告扫描进度，便于大文件夹显示计数。
+// 目录未变化时返回缓存的扫描结果，refresh 为 true 时强制重新扫描。
+// pattern 为文件名通配符（如 "*_cycle.xlsx"，不区分大小写），设置时只返回匹配的文件。
+// 无法读取的文件或子目录不会中断扫描，在 warnings 中列出
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn read_directory(
+    app: AppHandle,
     path: String,
-    size: u64,
-    is_excel: bool,
-    last_modified: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct ProcessConfig {
-    // 基础配置
-    input_folder: String,
-    output_folder: String,
-
-    // 异常检测配置
-    outlier_method: String,
-    boxplot_threshold_discharge: f64,
-    boxplot_threshold_efficiency: f64,
-    zscore_threshold_discharge: f64,
-    zscore_threshold_efficiency: f64,
-    zscore_mad_constant: f64,
-
-    // 其他配置（可选，使用默认值）
-    #[serde(default)]
-    reference_channel_method: String,
-    #[serde(default)]
-    verbose: bool,
-    #[serde(default)]
-    enable_progress_bar: bool,
-}
-
-// Tauri命令：读取目录文件
-#[tauri::command]
-fn read_directory(path: String) -> Result<Vec<FileInfo>, String> {
-    let dir_path = Path::new(&path);
-    if !dir_path.exists() {
-        return Err("文件夹不存在".to_string());
-    }
-
-    let mut files = Vec::new();
-
-    match fs::read_dir(dir_path) {
-        Ok(entries) => {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let file_name = entry.file_name().to_string_lossy().to_string();
-                    let file_path = entry.path().to_string_lossy().to_string();
-                    let is_excel = file_name.ends_with(".xlsx") || file_name.ends_with(".xls");
-
-                    // 只包含Excel文件
-                    if is_excel {
-                        let metadata = entry.metadata().map_err(|e| format!("读取文件元数据失败: {}", e))?;
-                        let size = metadata.len();
-                        let last_modified = format!("{:?}", metadata.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH));
-
-                        files.push(FileInfo {
-                            name: file_name,
-                            path: file_path,
-                            size,
-                            is_excel,
-                            last_modified,
-                        });
-                    }
+    recursive: Option<bool>,
+    max_depth: Option<usize>,
+    include_temp: Option<bool>,
+    include_hash: Option<bool>,
+    check_locked: Option<bool>,
+    follow_symlinks: Option<bool>,
+    report_progress: Option<bool>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort_by: Option<SortBy>,
+    descending: Option<bool>,
+    refresh: Option<bool>,
+    pattern: Option<String>,
+) -> Result<DirectoryPage, AppError> {
+    run_blocking(move || {
+        scan::check_directory(&path)?;
+        let dir_path = Path::new(&path);
+        // 通配符在扫描前校验；缓存中保存完整列表，不同通配符共用同一次扫描
+        let pattern = scan::file_pattern(pattern.as_deref())?;
+
+        let options = ScanOptions {
+            recursive: recursive.unwrap_or(false),
+            max_depth,
+            include_temp: include_temp.unwrap_or(false),
+            include_hash: include_hash.unwrap_or(false),
+            check_locked: check_locked.unwrap_or(false),
+            follow_symlinks: follow_symlinks.unwrap_or(false),
+        };
+        let report_progress = report_progress.unwrap_or(false);
+        let mut result = app.state::<DirectoryCache>().scan(
+            dir_path,
+            &options,
+            refresh.unwrap_or(false),
+            &mut |progress| {
+                if report_progress {
+                    let _ = app.emit(scan::SCAN_PROGRESS_EVENT, progress);
                 }
-            }
-        }
-        Err(e) => return Err(format!("读取文件夹失败: {}", e)),
-    }
-
-    Ok(files)
-}
-
-// Tauri命令：处理电池数据（调用Python模块）
-#[tauri::command]
-fn process_battery_data(config: ProcessConfig) -> Result<String, String> {
-    // 检查输入文件夹是否存在
-    if !Path::new(&config.input_folder).exists() {
-        return Err("输入文件夹不存在".to_string());
-    }
-
-    // 创建输出文件夹（如果不存在）
-    let output_folder = if config.output_folder.is_empty() {
-        config.input_folder.clone()
-    } else {
-        config.output_folder.clone()
-    };
-
-    if let Err(e) = fs::create_dir_all(&output_folder) {
-        return Err(format!("创建输出文件夹失败: {}", e));
-    }
-
-    // 构建Python脚本路径（相对于Tauri应用）
-    let python_script = "../main.py";
-
-    // 检查Python脚本是否存在
-    if !Path::new(python_script).exists() {
-        return Err(format!("Python脚本不存在: {}", python_script));
-    }
-
-    // 构建命令参数（传递所有配置）
-    let mut cmd = Command::new("python");
-    cmd.arg(python_script)
-       .arg("--input_folder").arg(&config.input_folder)
-       .arg("--output_folder").arg(&output_folder)
-       .arg("--outlier_method").arg(&config.outlier_method)
-       .arg("--reference_channel_method").arg(&config.reference_channel_method)
-       .arg("--boxplot_threshold_discharge").arg(config.boxplot_threshold_discharge.to_string())
-       .arg("--boxplot_threshold_efficiency").arg(config.boxplot_threshold_efficiency.to_string())
-       .arg("--zscore_threshold_discharge").arg(config.zscore_threshold_discharge.to_string())
-       .arg("--zscore_threshold_efficiency").arg(config.zscore_threshold_efficiency.to_string())
-       .arg("--zscore_mad_constant").arg(config.zscore_mad_constant.to_string());
-
-    // 添加可选参数
-    if config.verbose {
-        cmd.arg("--verbose");
-    }
-
-    // 执行Python脚本
-    match cmd.output() {
-        Ok(output) => {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                Ok(format!("✅ 数据处理完成！\n\n{}", stdout))
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                Err(format!("❌ Python脚本执行失败:\n{}", stderr))
-            }
+            },
+        )?;
+        recent::record_quietly(&app, &path);
+        if let Some(pattern) = pattern {
+            result.files = scan::filter_by_pattern(result.files, &pattern);
         }
-        Err(e) => Err(format!("❌ 启动Python脚本失败: {}", e))
-    }
+        Ok(scan::paginate(
+            result,
+            sort_by.unwrap_or_default(),
+            descending.unwrap_or(false),
+            offset.unwrap_or(0),
+            limit,
+        ))
+    })
+    .await
+}
+
+// Tauri命令：列出 since_unix（Unix时间戳，秒）之后新增或修改的数据文件，用于只处理新文件。
+// since_unix 未指定时使用该文件夹最近一次成功运行的开始时间，从未运行过时返回全部文件
+#[tauri::command]
+async fn read_directory_since(
+    app: AppHandle,
+    path: String,
+    since_unix: Option<u64>,
+    recursive: Option<bool>,
+) -> Result<Vec<FileInfo>, AppError> {
+    run_blocking(move || {
+        scan::check_directory(&path)?;
+        let dir_path = Path::new(&path);
+        let since = match since_unix {
+            Some(since) => Some(since),
+            None => lastrun::get(&app, &path)?,
+        };
+        let options = ScanOptions { recursive: recursive.unwrap_or(false), ..Default::default() };
+        // 文件内容变化不会更新目录的修改时间，因此不使用目录缓存
+        let files = scan::scan_directory(dir_path, &options)?;
+        Ok(match since {
+            Some(since) => scan::changed_since(files, since),
+            None => files,
+        })
+    })
+    .await
+}
+
+// Tauri命令：获取文件夹最近一次成功运行的开始时间（Unix时间戳，秒），从未成功运行时为 None
+#[tauri::command]
+fn get_last_run(app: AppHandle, path: String) -> Result<Option<u64>, AppError> {
+    lastrun::get(&app, &path)
+}
+
+// Tauri命令：检查路径是否存在、是文件夹还是文件，便于前端在选择后预先校验
+#[tauri::command]
+fn inspect_path(path: String) -> PathInfo {
+    scan::inspect_path(&path)
+}
+
+// Tauri命令：统计文件夹中Excel/CSV文件的数量和总大小
+#[tauri::command]
+async fn directory_stats(path: String, recursive: Option<bool>) -> Result<DirectoryStats, AppError> {
+    run_blocking(move || {
+        scan::check_directory(&path)?;
+        let dir_path = Path::new(&path);
+        let options = ScanOptions {
+            recursive: recursive.unwrap_or(false),
+            ..ScanOptions::default()
+        };
+        scan::directory_stats(dir_path, &options)
+    })
+    .await
+}
+
+// Tauri命令：查找文件夹中内容重复的数据文件（按内容哈希分组）
+#[tauri::command]
+async fn find_duplicates(path: String, recursive: Option<bool>) -> Result<Vec<DuplicateGroup>, AppError> {
+    run_blocking(move || {
+        scan::check_directory(&path)?;
+        let dir_path = Path::new(&path);
+        let options = ScanOptions {
+            recursive: recursive.unwrap_or(false),
+            ..ScanOptions::default()
+        };
+        scan::find_duplicates(dir_path, &options)
+    })
+    .await
+}
+
+// Tauri命令：只处理指定的数据文件，返回整体结果和每个文件的结果
+#[tauri::command]
+async fn process_files(
+    app: AppHandle,
+    paths: Vec<String>,
+    config: ProcessConfig,
+) -> Result<FilesOutcome, AppError> {
+    run_blocking(move || runner::process_files(&app, config, paths)).await
+}
+
+// Tauri命令：处理电池数据（调用Python模块），dry_run 时只返回处理计划
+// 在阻塞线程池中运行，避免占用命令线程，处理期间其他命令保持可用
+#[tauri::command]
+async fn process_battery_data(app: AppHandle, config: ProcessConfig) -> Result<ProcessOutcome, AppError> {
+    recent::record_quietly(&app, &config.input_folder);
+    run_blocking(move || runner::execute(&app, config)).await
+}
+
+// Tauri命令：批量处理 input_folders 中的多个文件夹，返回每个文件夹的结果
+#[tauri::command]
+async fn process_battery_batch(
+    app: AppHandle,
+    config: ProcessConfig,
+) -> Result<Vec<ProcessOutcome>, AppError> {
+    run_blocking(move || Ok(batch::run_batch(&app, config))).await
+}
+
+// Tauri命令：列出将要处理的输入文件及其大小（count_rows 为 true 时另外统计行数），按 queue_order 排序
+#[tauri::command]
+async fn estimate_jobs(
+    config: ProcessConfig,
+    count_rows: Option<bool>,
+) -> Result<Vec<FileEstimate>, AppError> {
+    run_blocking(move || estimate::estimate_jobs(&config, count_rows.unwrap_or(false))).await
+}
+
+// Tauri命令：校验处理配置，返回字段级错误列表（为空表示有效）
+#[tauri::command]
+fn validate_config(config: ProcessConfig) -> Vec<FieldError> {
+    config.validate()
+}
+
+// Tauri命令：获取配置项的可选值（异常检测方法、参考通道选择方法、日志级别）
+#[tauri::command]
+fn get_config_options() -> ConfigOptions {
+    ConfigOptions::current()
+}
+
+// Tauri命令：获取 ProcessConfig 的JSON Schema（字段类型、默认值和可选值）
+#[tauri::command]
+fn get_config_schema() -> RootSchema {
+    config::schema()
+}
+
+// Tauri命令：检查Python环境、依赖包与分析脚本是否就绪
+#[tauri::command]
+async fn check_environment(
+    app: AppHandle,
+    python_executable: Option<String>,
+    venv_path: Option<String>,
+    conda_env: Option<String>,
+    script_path: Option<String>,
+) -> Result<EnvironmentStatus, AppError> {
+    run_blocking(move || {
+        Ok(environment::check(
+            &app,
+            python_executable.as_deref(),
+            venv_path.as_deref(),
+            conda_env.as_deref(),
+            script_path.as_deref(),
+        ))
+    })
+    .await
+}
+
+// Tauri命令：获取应用、Tauri、Python和分析脚本的版本（本次运行中缓存）
+#[tauri::command]
+async fn get_version_info(app: AppHandle) -> Result<VersionInfo, AppError> {
+    run_blocking(move || Ok(environment::version_info(&app))).await
+}
+
+// Tauri命令：获取构建模式（debug/release）、操作系统和CPU架构
+#[tauri::command]
+fn get_runtime_info() -> RuntimeInfo {
+    environment::runtime_info()
+}
+
+// Tauri命令：用默认配置处理打包的示例数据，逐步检查Python、分析脚本和输出是否正常
+#[tauri::command]
+async fn run_self_test(app: AppHandle) -> Result<SelfTestReport, AppError> {
+    run_blocking(move || selftest::run(&app)).await
+}
+
+// Tauri命令：预览Excel工作表的表头和前 rows 行（默认第一个工作表，最多500行）
+#[tauri::command]
+async fn preview_excel(
+    path: String,
+    sheet: Option<String>,
+    rows: Option<usize>,
+) -> Result<ExcelPreview, AppError> {
+    run_blocking(move || excel::preview(&path, sheet.as_deref(), rows.unwrap_or(50))).await
+}
+
+// Tauri命令：预览CSV文件的表头和前 rows 行（最多500行）；encoding 未指定时自动识别BOM、UTF-8和GBK
+#[tauri::command]
+async fn preview_csv(
+    path: String,
+    encoding: Option<String>,
+    rows: Option<usize>,
+) -> Result<CsvPreview, AppError> {
+    run_blocking(move || csvfile::preview(&path, encoding.as_deref(), rows.unwrap_or(50))).await
+}
+
+// Tauri命令：按顺序列出工作簿中的工作表名称
+#[tauri::command]
+async fn list_sheets(path: String) -> Result<Vec<String>, AppError> {
+    run_blocking(move || excel::list_sheets(&path)).await
+}
+
+// Tauri命令：检查数据文件的行数和必需列，便于运行前发现格式错误
+#[tauri::command]
+async fn validate_battery_file(path: String, sheet: Option<String>) -> Result<BatteryFileCheck, AppError> {
+    run_blocking(move || excel::validate_battery_file(&path, sheet.as_deref())).await
+}
+
+// Tauri命令：根据工作表名和表头识别数据文件由哪种测试仪器导出，并给出建议的分析设置
+#[tauri::command]
+async fn detect_format(path: String) -> Result<FormatDetection, AppError> {
+    run_blocking(move || vendor::detect_format(&path)).await
+}
+
+// Tauri命令：检查文件夹中所有Excel/CSV文件的表头是否一致（最多检查500个文件），列出与多数文件不同的文件
+#[tauri::command]
+async fn check_folder_consistency(
+    path: String,
+    sheet: Option<String>,
+) -> Result<FolderConsistency, AppError> {
+    run_blocking(move || consistency::check_folder_consistency(&path, sheet.as_deref())).await
+}
+
+// Tauri命令：读取工作表中A1格式区域（如 "A1:D20"）的单元格值
+#[tauri::command]
+async fn read_cell_range(
+    path: String,
+    sheet: Option<String>,
+    range: String,
+) -> Result<Vec<Vec<String>>, AppError> {
+    run_blocking(move || excel::read_cell_range(&path, sheet.as_deref(), &range)).await
+}
+
+// Tauri命令：读取工作表中 x_column 和 y_columns 列的数值，返回可直接绘图的等长数组
+#[tauri::command]
+async fn extract_series(
+    path: String,
+    sheet: Option<String>,
+    x_column: String,
+    y_columns: Vec<String>,
+) -> Result<SeriesData, AppError> {
+    run_blocking(move || excel::extract_series(&path, sheet.as_deref(), &x_column, &y_columns)).await
+}
+
+// Tauri命令：将 .xls 转换为 .xlsx，返回输出文件路径
+#[tauri::command]
+async fn convert_to_xlsx(path: String, output_path: Option<String>) -> Result<String, AppError> {
+    run_blocking(move || excel::convert_to_xlsx(&path, output_path.as_deref())).await
+}
+
+// Tauri命令：将多个数据文件合并为一个工作簿（每个文件一个工作表，或按相同表头追加数据行）
+#[tauri::command]
+async fn merge_excel(
+    paths: Vec<String>,
+    output_path: String,
+    mode: MergeMode,
+    sheet: Option<String>,
+) -> Result<MergeResult, AppError> {
+    run_blocking(move || excel::merge_excel(&paths, &output_path, mode, sheet.as_deref())).await
+}
+
+// Tauri命令：根据结果文件夹中的汇总工作簿生成单文件HTML报告，返回报告路径
+#[tauri::command]
+async fn generate_report(result_dir: String, output_html: Option<String>) -> Result<String, AppError> {
+    run_blocking(move || report::generate_report(&result_dir, output_html.as_deref())).await
+}
+
+// Tauri命令：预估文件夹（同一批次的数据文件）中将被选为参考通道的文件，method 为空时使用脚本默认方法
+#[tauri::command]
+async fn preview_reference_channel(
+    path: String,
+    method: Option<String>,
+) -> Result<ReferencePreview, AppError> {
+    run_blocking(move || reference::preview_reference_channel(&path, method.as_deref())).await
+}
+
+// Tauri命令：对比两个结果文件夹中最新的汇总工作簿，返回只在一侧出现的数据行和逐项数值差异
+#[tauri::command]
+async fn compare_results(dir_a: String, dir_b: String) -> Result<RunComparison, AppError> {
+    run_blocking(move || compare::compare_results(&dir_a, &dir_b)).await
+}
+
+// Tauri命令：读取结果文件夹中的运行清单（run_manifest.json），查看结果是用哪些配置和版本生成的
+#[tauri::command]
+async fn read_manifest(folder: String) -> Result<RunManifest, AppError> {
+    run_blocking(move || manifest::read(&folder)).await
+}
+
+// Tauri命令：读取分析脚本写在结果文件夹中的 summary.json（处理文件数、剔除的异常点数、平均效率等）
+#[tauri::command]
+async fn read_summary(folder: String) -> Result<RunSummary, AppError> {
+    run_blocking(move || summary::read_summary(&folder)).await
+}
+
+// Tauri命令：删除 root 文件夹中选中的文件（默认移到回收站），返回每个文件的结果；不会删除文件夹或 root 之外的文件
+#[tauri::command]
+async fn delete_files(
+    root: String,
+    paths: Vec<String>,
+    to_trash: Option<bool>,
+) -> Result<Vec<DeleteOutcome>, AppError> {
+    run_blocking(move || fileops::delete_files(&root, &paths, to_trash.unwrap_or(true))).await
+}
+
+// Tauri命令：批量重命名 root 文件夹中的文件，全部校验通过后才执行，中途失败时撤销已完成的重命名
+#[tauri::command]
+async fn rename_files(root: String, ops: Vec<RenameOp>) -> Result<Vec<RenameOutcome>, AppError> {
+    run_blocking(move || fileops::rename_files(&root, &ops)).await
+}
+
+// Tauri命令：在系统文件管理器中显示文件或文件夹
+#[tauri::command]
+fn open_in_file_manager(path: String) -> Result<(), AppError> {
+    opener::reveal(&path)
+}
+
+// Tauri命令：用系统默认程序打开结果文件
+// 需要短暂等待打开程序的退出状态，因此在阻塞线程池中执行
+#[tauri::command]
+async fn open_file(path: String) -> Result<(), AppError> {
+    run_blocking(move || opener::open_file(&path)).await
+}
+
+// Tauri命令：保存配置预设（同名覆盖）
+#[tauri::command]
+fn save_preset(app: AppHandle, name: String, config: ProcessConfig) -> Result<(), AppError> {
+    presets::save(&app, &name, &config)
+}
+
+// Tauri命令：加载配置预设
+#[tauri::command]
+fn load_preset(app: AppHandle, name: String) -> Result<ProcessConfig, AppError> {
+    presets::load(&app, &name)
+}
+
+// Tauri命令：列出所有配置预设
+#[tauri::command]
+fn list_presets(app: AppHandle) -> Result<Vec<String>, AppError> {
+    presets::list(&app)
+}
+
+// Tauri命令：删除配置预设
+#[tauri::command]
+fn delete_preset(app: AppHandle, name: String) -> Result<(), AppError> {
+    presets::delete(&app, &name)
+}
+
+// Tauri命令：获取最近使用的文件夹（最新的在前，最多10个）
+#[tauri::command]
+fn get_recent_folders(app: AppHandle) -> Result<Vec<String>, AppError> {
+    recent::list(&app)
+}
+
+// Tauri命令：清空最近使用的文件夹
+#[tauri::command]
+fn clear_recent_folders(app: AppHandle) -> Result<(), AppError> {
+    recent::clear(&app)
+}
+
+// Tauri命令：获取运行日志文件路径，便于用户附加到问题报告
+#[tauri::command]
+fn get_log_path(app: AppHandle) -> Result<String, AppError> {
+    runlog::log_path(&app).map(|path| path.to_string_lossy().to_string())
+}
+
+// Tauri命令：获取应用的配置、数据、日志和缓存目录路径，便于用户查找预设和日志
+#[tauri::command]
+fn get_app_dirs(app: AppHandle) -> Result<AppDirs, AppError> {
+    appdirs::app_dirs(&app)
+}
+
+// Tauri命令：在系统文件管理器中打开指定的应用目录（Config/Data/Log/Cache）
+#[tauri::command]
+fn open_app_dir(app: AppHandle, which: AppDir) -> Result<(), AppError> {
+    appdirs::open(&app, which)
+}
+
+// Tauri命令：设置错误信息的语言（"zh" 或 "en"，也接受 "zh-CN"、"en-US" 等写法）
+#[tauri::command]
+fn set_locale(lang: String) -> Result<(), AppError> {
+    let locale = i18n::Locale::parse(&lang).ok_or(AppError::UnsupportedLocale(lang))?;
+    i18n::set(locale);
+    Ok(())
+}
+
+// Tauri命令：将分析任务加入队列（按入队顺序执行），返回任务ID；状态通过 battery://job-status 事件通知。
+// 按大小排序时入队需要扫描输入文件夹，因此在后台线程执行
+#[tauri::command]
+async fn enqueue_job(app: AppHandle, config: ProcessConfig) -> Result<u64, AppError> {
+    run_blocking(move || {
+        recent::record_quietly(&app, &config.input_folder);
+        Ok(app.state::<JobQueue>().enqueue(&app, config))
+    })
+    .await
+}
+
+// Tauri命令：列出队列中的所有任务及队列是否已暂停
+#[tauri::command]
+fn list_jobs(queue: State<'_, JobQueue>) -> QueueInfo {
+    queue.list()
+}
+
+// Tauri命令：暂停队列，排队中的任务保留但不再开始，运行中的任务照常完成；已暂停时返回 false
+#[tauri::command]
+fn pause_queue(app: AppHandle, queue: State<'_, JobQueue>) -> bool {
+    queue.pause(&app)
+}
+
+// Tauri命令：继续处理排队中的任务，未暂停时返回 false
+#[tauri::command]
+fn resume_queue(app: AppHandle, queue: State<'_, JobQueue>) -> bool {
+    queue.resume(&app)
+}
+
+// Tauri命令：取消排队中或运行中的任务，任务不存在或已结束时返回 false
+#[tauri::command]
+fn cancel_job(app: AppHandle, queue: State<'_, JobQueue>, job_id: u64) -> bool {
+    queue.cancel(&app, job_id)
+}
+
+// Tauri命令：取消全部排队中和运行中的任务，返回被取消的任务数
+#[tauri::command]
+fn cancel_all_jobs(app: AppHandle, queue: State<'_, JobQueue>) -> usize {
+    queue.cancel_all(&app)
+}
+
+// Tauri命令：设置队列同时运行的任务数（默认1）
+#[tauri::command]
+fn set_queue_concurrency(app: AppHandle, queue: State<'_, JobQueue>, concurrency: usize) {
+    queue.set_concurrency(&app, concurrency)
+}
+
+// Tauri命令：查看已结束任务的输出（stdout/stderr，过长时只保留末尾）和结果；
+// 任务不存在、尚未结束或记录已被淘汰时返回 None
+#[tauri::command]
+fn get_job_output(store: State<'_, JobOutputStore>, job_id: u64) -> Option<JobOutput> {
+    store.get(job_id)
+}
+
+// Tauri命令：设置保留输出的已结束任务数（默认20），超出时淘汰最早的记录
+#[tauri::command]
+fn set_job_output_limit(store: State<'_, JobOutputStore>, limit: usize) {
+    store.set_limit(limit)
+}
+
+// Tauri命令：取消正在运行的分析任务（未指定任务ID时取消全部）
+#[tauri::command]
+fn cancel_processing(registry: State<'_, ProcessRegistry>, job_id: Option<u64>) -> usize {
+    registry.cancel(job_id)
+}
+
+// Tauri命令：监听文件夹中Excel/CSV文件的新增、删除和修改，变化通过 battery://fs-change 事件通知
+#[tauri::command]
+fn watch_directory(
+    app: AppHandle,
+    watchers: State<'_, WatcherRegistry>,
+    path: String,
+    recursive: Option<bool>,
+) -> Result<(), AppError> {
+    watchers.watch(&app, &path, recursive.unwrap_or(false))
+}
+
+// Tauri命令：停止监听文件夹，返回该文件夹之前是否在监听
+#[tauri::command]
+fn unwatch_directory(watchers: State<'_, WatcherRegistry>, path: String) -> bool {
+    watchers.unwatch(&path)
+}
+
+// Tauri命令：返回日志文件（如 get_log_path 返回的运行日志）的最后 lines 行（默认200行）；
+// follow 为 true 时继续跟踪，追加的行通过 battery://log-tail 事件发送，文件被截断或替换后从头读取
+#[tauri::command]
+fn tail_log(
+    app: AppHandle,
+    tails: State<'_, LogTailRegistry>,
+    path: String,
+    lines: Option<usize>,
+    follow: Option<bool>,
+) -> Result<Vec<String>, AppError> {
+    tails.tail(&app, &path, lines.unwrap_or(200), follow.unwrap_or(false))
+}
+
+// Tauri命令：停止跟踪日志文件，返回该文件之前是否在跟踪
+#[tauri::command]
+fn stop_tail_log(tails: State<'_, LogTailRegistry>, path: String) -> bool {
+    tails.stop(&path)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -141,9 +623,73 @@ pub fn run() {
   tauri::Builder::default()
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_fs::init())
+    .manage(ProcessRegistry::default())
+    .manage(WatcherRegistry::default())
+    .manage(JobQueue::default())
+    .manage(LogTailRegistry::default())
+    .manage(DirectoryCache::default())
+    .manage(JobOutputStore::default())
     .invoke_handler(tauri::generate_handler![
         read_directory,
-        process_battery_data
+        read_directory_since,
+        get_last_run,
+        inspect_path,
+        directory_stats,
+        find_duplicates,
+        process_battery_data,
+        process_battery_batch,
+        process_files,
+        estimate_jobs,
+        validate_config,
+        get_config_options,
+        get_config_schema,
+        check_environment,
+        run_self_test,
+        get_version_info,
+        get_runtime_info,
+        preview_excel,
+        preview_csv,
+        list_sheets,
+        validate_battery_file,
+        detect_format,
+        check_folder_consistency,
+        read_cell_range,
+        extract_series,
+        convert_to_xlsx,
+        merge_excel,
+        generate_report,
+        compare_results,
+        read_manifest,
+        read_summary,
+        preview_reference_channel,
+        open_in_file_manager,
+        open_file,
+        delete_files,
+        rename_files,
+        save_preset,
+        load_preset,
+        list_presets,
+        delete_preset,
+        get_recent_folders,
+        clear_recent_folders,
+        get_log_path,
+        get_app_dirs,
+        open_app_dir,
+        set_locale,
+        cancel_processing,
+        enqueue_job,
+        list_jobs,
+        cancel_job,
+        cancel_all_jobs,
+        pause_queue,
+        resume_queue,
+        set_queue_concurrency,
+        get_job_output,
+        set_job_output_limit,
+        watch_directory,
+        unwatch_directory,
+        tail_log,
+        stop_tail_log
     ])
     .setup(|app| {
       if cfg!(debug_assertions) {
@@ -153,8 +699,20 @@ pub fn run() {
             .build(),
         )?;
       }
+      // 启动时清理上次异常退出遗留的临时配置文件，不必等到下一次分析
+      if let Err(e) = tempfiles::prepare(app.handle()) {
+        log::warn!("清理遗留的临时文件失败: {}", e);
+      }
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while running tauri application")
+    .run(|app, event| {
+      // 退出时停止所有文件夹监听和日志跟踪，并终止仍在运行的Python进程，避免其继续占用输出文件
+      if let RunEvent::Exit = event {
+        app.state::<WatcherRegistry>().clear();
+        app.state::<LogTailRegistry>().clear();
+        app.state::<ProcessRegistry>().cancel(None);
+      }
+    });
 }
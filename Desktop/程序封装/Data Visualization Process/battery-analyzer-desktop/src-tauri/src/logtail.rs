@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppError;
+
+// 日志文件新增内容事件名
+const LOG_TAIL_EVENT: &str = "battery://log-tail";
+// 从文件末尾向前读取时每次读取的字节数
+const TAIL_CHUNK: u64 = 64 * 1024;
+// tail_log 最多返回的行数
+pub const MAX_TAIL_LINES: usize = 5000;
+// 没有文件事件时也定期检查一次（网络共享上可能收不到事件）
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// 日志新增内容事件负载；rotated 为 true 表示文件被截断或替换，lines 从新文件开头读取
+#[derive(Debug, Clone, Serialize)]
+struct LogTailEvent {
+    path: String,
+    lines: Vec<String>,
+    rotated: bool,
+}
+
+// 返回日志文件的最后 lines 行（最多 MAX_TAIL_LINES 行），以及读取时的文件长度（实时跟踪的起点）
+fn read_tail(path: &str, lines: usize) -> Result<(Vec<String>, u64), AppError> {
+    let lines = lines.min(MAX_TAIL_LINES);
+    if !Path::new(path).is_file() {
+        return Err(AppError::FileNotFound(path.to_string()));
+    }
+    let mut file = File::open(path).map_err(|e| AppError::io("读取日志文件失败", e))?;
+    let len = file.metadata().map_err(|e| AppError::io("读取日志文件失败", e))?.len();
+
+    // 从末尾向前按块读取，直到包含足够的换行或到达文件开头
+    let mut buf: Vec<u8> = Vec::new();
+    let mut pos = len;
+    while pos > 0 && buf.iter().filter(|&&b| b == b'\n').count() <= lines {
+        let start = pos.saturating_sub(TAIL_CHUNK);
+        let mut chunk = vec![0; (pos - start) as usize];
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.read_exact(&mut chunk))
+            .map_err(|e| AppError::io("读取日志文件失败", e))?;
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+        pos = start;
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let all: Vec<&str> = text.lines().collect();
+    let skip = all.len().saturating_sub(lines);
+    Ok((all[skip..].iter().map(|line| line.to_string()).collect(), len))
+}
+
+// 正在实时跟踪的日志文件，watcher 被移除（drop）后后台线程自动退出
+#[derive(Default)]
+pub struct LogTailRegistry {
+    tails: Mutex<HashMap<PathBuf, RecommendedWatcher>>,
+}
+
+impl LogTailRegistry {
+    // 返回最后 lines 行；follow 为 true 时开始实时跟踪，之后追加的行通过 battery://log-tail 事件发送
+    pub fn tail(
+        &self,
+        app: &AppHandle,
+        path: &str,
+        lines: usize,
+        follow: bool,
+    ) -> Result<Vec<String>, AppError> {
+        let (lines, len) = read_tail(path, lines)?;
+        if follow {
+            self.follow(app, path, len)?;
+        }
+        Ok(lines)
+    }
+
+    // 监听日志所在的文件夹而不是文件本身，文件被删除后重建（轮转）时仍能收到事件
+    fn follow(&self, app: &AppHandle, path: &str, offset: u64) -> Result<(), AppError> {
+        let file = PathBuf::from(path);
+        let mut tails = self.tails.lock().unwrap();
+        if tails.contains_key(&file) {
+            return Ok(());
+        }
+        let dir = match file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .map_err(|e| watch_failed(path, e))?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| watch_failed(path, e))?;
+
+        let mut follower = Follower {
+            app: app.clone(),
+            path: file.clone(),
+            offset,
+            identity: fs::metadata(&file).ok().map(|metadata| identity(&metadata)),
+            partial: Vec::new(),
+        };
+        thread::spawn(move || follower.run(rx));
+
+        tails.insert(file, watcher);
+        Ok(())
+    }
+
+    // 停止跟踪，返回该文件之前是否在跟踪
+    pub fn stop(&self, path: &str) -> bool {
+        self.tails.lock().unwrap().remove(Path::new(path)).is_some()
+    }
+
+    // 停止全部跟踪（应用退出时调用）
+    pub fn clear(&self) {
+        self.tails.lock().unwrap().clear();
+    }
+}
+
+// 文件身份：Unix上为 (设备号, inode)，其他平台退化为创建时间
+#[cfg(unix)]
+type FileIdentity = (u64, u64);
+#[cfg(not(unix))]
+type FileIdentity = Option<std::time::SystemTime>;
+
+#[cfg(unix)]
+fn identity(metadata: &Metadata) -> FileIdentity {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn identity(metadata: &Metadata) -> FileIdentity {
+    metadata.created().ok()
+}
+
+struct Follower {
+    app: AppHandle,
+    path: PathBuf,
+    // 已读取到的位置
+    offset: u64,
+    identity: Option<FileIdentity>,
+    // 末尾尚未以换行结束的内容，等下次读到换行再发送
+    partial: Vec<u8>,
+}
+
+impl Follower {
+    fn run(&mut self, rx: mpsc::Receiver<Event>) {
+        loop {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(event) if !event.paths.iter().any(|path| path == &self.path) => continue,
+                Ok(_) | Err(RecvTimeoutError::Timeout) => {
+                    // 读取失败（如文件轮转期间暂时不存在）时等待下一次事件
+                    let _ = self.read_appended();
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    fn read_appended(&mut self) -> io::Result<()> {
+        let metadata = fs::metadata(&self.path)?;
+        let current = identity(&metadata);
+        // 文件被替换或截断时从头读取
+        let rotated = self.identity.is_some_and(|previous| previous != current)
+            || metadata.len() < self.offset;
+        if rotated {
+            self.offset = 0;
+            self.partial.clear();
+        }
+        self.identity = Some(current);
+        if metadata.len() == self.offset && !rotated {
+            return Ok(());
+        }
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)?;
+        self.offset += appended.len() as u64;
+        self.partial.extend_from_slice(&appended);
+
+        // 只发送完整的行
+        let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(());
+        };
+        let complete: Vec<u8> = self.partial.drain(..=last_newline).collect();
+        let lines = String::from_utf8_lossy(&complete)
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+        let _ = self.app.emit(
+            LOG_TAIL_EVENT,
+            LogTailEvent {
+                path: self.path.to_string_lossy().to_string(),
+                lines,
+                rotated,
+            },
+        );
+        Ok(())
+    }
+}
+
+fn watch_failed(path: &str, e: notify::Error) -> AppError {
+    AppError::WatchFailed { path: path.to_string(), reason: e.to_string() }
+}
@@ -0,0 +1,93 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::config::ProcessConfig;
+use crate::environment;
+use crate::error::AppError;
+use crate::output::OutputFile;
+use crate::python::PythonRuntime;
+use crate::runner;
+use crate::scan;
+
+// 运行清单的文件名，写在输出文件夹中
+pub const MANIFEST_FILE: &str = "run_manifest.json";
+
+// 一次成功运行所使用的配置和环境，便于日后追溯结果是如何得到的
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunManifest {
+    // 运行结束时间（RFC 3339，UTC）
+    pub created_at: String,
+    pub app_version: String,
+    pub python: String,
+    pub python_version: Option<String>,
+    pub script: String,
+    // 脚本不支持 --version 时为 None
+    pub script_version: Option<String>,
+    pub input_files: Vec<String>,
+    // 本次运行新建或更新的文件（相对输出文件夹的路径），旧版清单中没有该字段
+    #[serde(default)]
+    pub output_files: Vec<String>,
+    // 实际使用的配置（含 deterministic 和 seed），路径均为绝对路径
+    pub config: ProcessConfig,
+}
+
+impl RunManifest {
+    pub fn new(
+        app: &AppHandle,
+        config: &ProcessConfig,
+        output_folder: &str,
+        output_files: &[OutputFile],
+        python: &PythonRuntime,
+        script: &Path,
+    ) -> Self {
+        let mut config = config.clone();
+        config.output_folder = output_folder.to_string();
+        let input_files = runner::input_files(&config)
+            .map(|files| files.into_iter().map(|(path, _, _)| path).collect())
+            .unwrap_or_default();
+        let output_files = output_files
+            .iter()
+            .filter_map(|file| {
+                let path = Path::new(&file.path).strip_prefix(output_folder).ok()?;
+                Some(path.to_string_lossy().to_string())
+            })
+            .collect();
+        RunManifest {
+            created_at: Utc::now().to_rfc3339(),
+            app_version: app.package_info().version.to_string(),
+            python: python.executable.to_string_lossy().to_string(),
+            python_version: environment::python_version(python).ok(),
+            script: script.to_string_lossy().to_string(),
+            script_version: environment::script_version(python, script),
+            input_files,
+            output_files,
+            config,
+        }
+    }
+}
+
+// 写入（覆盖）输出文件夹中的运行清单
+pub fn write(output_folder: &Path, manifest: &RunManifest) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| AppError::io("序列化运行清单失败", io::Error::from(e)))?;
+    fs::write(output_folder.join(MANIFEST_FILE), json).map_err(|e| AppError::io("保存运行清单失败", e))
+}
+
+// 读取结果文件夹中的运行清单，文件夹中没有清单时返回 FileNotFound
+pub fn read(folder: &str) -> Result<RunManifest, AppError> {
+    scan::check_directory(folder)?;
+    let path = Path::new(folder).join(MANIFEST_FILE);
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(AppError::FileNotFound(path.to_string_lossy().to_string()))
+        }
+        Err(e) => return Err(AppError::io("读取运行清单失败", e)),
+    };
+    serde_json::from_str(&json).map_err(|e| AppError::io("解析运行清单失败", io::Error::from(e)))
+}
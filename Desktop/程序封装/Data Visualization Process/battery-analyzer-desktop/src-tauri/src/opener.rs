@@ -0,0 +1,157 @@
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+
+// 等待打开程序退出的时间：找不到关联程序时 open/xdg-open 会很快以非零状态退出
+const HANDLER_CHECK: Duration = Duration::from_secs(2);
+
+// 在系统文件管理器中显示路径：目录直接打开，文件则打开所在目录并尽量选中该文件
+pub fn reveal(path: &str) -> Result<(), AppError> {
+    let target = Path::new(path);
+    if !target.exists() {
+        return Err(AppError::PathNotFound(path.to_string()));
+    }
+    let select = target.is_file();
+
+    #[cfg(target_os = "windows")]
+    let command = {
+        use std::os::windows::process::CommandExt;
+
+        let mut cmd = Command::new("explorer");
+        if select {
+            // explorer 自行解析 /select 参数，需要原样传入带引号的路径
+            cmd.raw_arg(format!("/select,\"{}\"", target.display()));
+        } else {
+            cmd.arg(target);
+        }
+        cmd
+    };
+
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut cmd = Command::new("open");
+        if select {
+            cmd.arg("-R");
+        }
+        cmd.arg(target);
+        cmd
+    };
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let command = {
+        // 优先通过 FileManager1 D-Bus 接口选中文件，不支持时退回到 xdg-open 打开所在目录
+        if select && show_items_via_dbus(target) {
+            return Ok(());
+        }
+        let dir = if select { target.parent().unwrap_or(target) } else { target };
+        let mut cmd = Command::new("xdg-open");
+        cmd.arg(dir);
+        cmd
+    };
+
+    launch(command, path)
+}
+
+// 用系统默认程序打开文件（如结果Excel、图表图片）
+pub fn open_file(path: &str) -> Result<(), AppError> {
+    let target = Path::new(path);
+    if !target.is_file() {
+        return Err(AppError::FileNotFound(path.to_string()));
+    }
+
+    #[cfg(target_os = "windows")]
+    let command = {
+        use std::os::windows::process::CommandExt;
+
+        // start 的第一个带引号参数是窗口标题，需传入空标题
+        let mut cmd = Command::new("cmd");
+        cmd.raw_arg(format!("/C start \"\" \"{}\"", target.display()));
+        cmd
+    };
+
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut cmd = Command::new("open");
+        cmd.arg(target);
+        cmd
+    };
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let command = {
+        let mut cmd = Command::new("xdg-open");
+        cmd.arg(target);
+        cmd
+    };
+
+    launch_checked(command, path)
+}
+
+// 启动打开程序并短暂等待：非零退出视为没有关联的默认程序，仍在运行则交给后台线程回收
+fn launch_checked(mut command: Command, path: &str) -> Result<(), AppError> {
+    let mut child = command.spawn().map_err(|e| AppError::OpenFailed {
+        path: path.to_string(),
+        reason: e.to_string(),
+    })?;
+    let started = Instant::now();
+    while started.elapsed() < HANDLER_CHECK {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(_)) => return Err(AppError::NoDefaultApp(path.to_string())),
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(_) => break,
+        }
+    }
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+// 启动外部程序，不等待其退出；后台线程负责回收子进程
+pub fn launch(mut command: Command, path: &str) -> Result<(), AppError> {
+    let mut child = command.spawn().map_err(|e| AppError::OpenFailed {
+        path: path.to_string(),
+        reason: e.to_string(),
+    })?;
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn show_items_via_dbus(target: &Path) -> bool {
+    let Ok(absolute) = target.canonicalize() else { return false };
+    Command::new("dbus-send")
+        .args([
+            "--session",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", file_uri(&absolute)))
+        .arg("string:")
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+// 将绝对路径转换为 file:// URI（对非安全字符做百分号编码）
+#[cfg(all(unix, not(target_os = "macos")))]
+fn file_uri(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
@@ -0,0 +1,387 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::Local;
+use serde::Serialize;
+use tempfile::Builder;
+
+use crate::config::OverwritePolicy;
+use crate::error::AppError;
+use crate::i18n::tr;
+
+// 文件快照：大小与修改时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+pub type Snapshot = HashMap<PathBuf, FileStamp>;
+
+// 可用空间低于该值时给出警告
+const LOW_DISK_SPACE_BYTES: u64 = 500 * 1024 * 1024;
+
+// 运行生成或更新的输出文件
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputFile {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    // true 表示新建，false 表示覆盖了已有文件
+    pub created: bool,
+}
+
+// 递归记录输出文件夹中的所有文件，用于运行前后对比
+pub fn snapshot_files(dir: &Path) -> Snapshot {
+    let mut files = HashMap::new();
+    collect_files(dir, &mut files);
+    files
+}
+
+fn collect_files(dir: &Path, files: &mut Snapshot) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files);
+        } else if file_type.is_file() {
+            let Ok(metadata) = entry.metadata() else { continue };
+            files.insert(
+                entry.path(),
+                FileStamp {
+                    size: metadata.len(),
+                    modified: metadata.modified().ok(),
+                },
+            );
+        }
+    }
+}
+
+// 记录已存在的目录：dir 的各级上级目录、dir 自身及其所有子目录，用于识别运行中新建的目录
+pub fn snapshot_dirs(dir: &Path) -> HashSet<PathBuf> {
+    let mut dirs = HashSet::new();
+    collect_dirs(dir, &mut dirs);
+    dirs
+}
+
+fn collect_dirs(dir: &Path, dirs: &mut HashSet<PathBuf>) {
+    dirs.extend(dir.ancestors().filter(|dir| dir.is_dir()).map(Path::to_path_buf));
+    collect_subdirs(dir, dirs);
+}
+
+fn collect_subdirs(dir: &Path, dirs: &mut HashSet<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            dirs.insert(entry.path());
+            collect_subdirs(&entry.path(), dirs);
+        }
+    }
+}
+
+// 返回运行后新建或被修改的文件（按路径排序）
+pub fn changed_files(before: &Snapshot, after: &Snapshot) -> Vec<OutputFile> {
+    let mut changed: Vec<OutputFile> = after
+        .iter()
+        .filter_map(|(path, stamp)| {
+            let created = match before.get(path) {
+                None => true,
+                Some(previous) if previous != stamp => false,
+                Some(_) => return None,
+            };
+            Some(OutputFile {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: path.to_string_lossy().to_string(),
+                size: stamp.size,
+                created,
+            })
+        })
+        .collect();
+    changed.sort_by(|a, b| a.path.cmp(&b.path));
+    changed
+}
+
+// 删除运行前快照中不存在的文件，再由深到浅删除 dirs_before 中不存在的空目录（包括新建的输出文件夹
+// 及其上级目录），返回已删除的路径（按路径排序）。
+// 运行前已存在的文件和目录即使被修改也保留；删除失败的文件跳过，仍有内容的目录保留
+pub fn remove_created_files(before: &Snapshot, dirs_before: &HashSet<PathBuf>, dir: &Path) -> Vec<String> {
+    let mut removed: Vec<String> = snapshot_files(dir)
+        .into_keys()
+        .filter(|path| !before.contains_key(path))
+        .filter(|path| match fs::remove_file(path) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("删除部分输出失败: {}: {}", path.display(), e);
+                false
+            }
+        })
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+
+    let mut dirs = HashSet::new();
+    collect_dirs(dir, &mut dirs);
+    let mut created: Vec<PathBuf> = dirs.into_iter().filter(|dir| !dirs_before.contains(dir)).collect();
+    created.sort_by_key(|dir| Reverse(dir.components().count()));
+    removed.extend(
+        created
+            .into_iter()
+            .filter(|dir| fs::remove_dir(dir).is_ok())
+            .map(|dir| dir.to_string_lossy().to_string()),
+    );
+    removed.sort();
+    removed
+}
+
+// 按覆盖策略确定实际输出文件夹（不创建目录）。
+// 冲突文件指输出文件夹中已有的、不属于输入数据的文件。输入数据只取输入文件夹顶层的文件，
+// 输出文件夹位于输入文件夹内（如默认的 input/output）时其中的旧结果不会被当作输入
+pub fn apply_overwrite_policy(
+    policy: OverwritePolicy,
+    input_folder: &Path,
+    output_folder: &str,
+) -> Result<String, AppError> {
+    match policy {
+        OverwritePolicy::Overwrite => Ok(output_folder.to_string()),
+        OverwritePolicy::Rename => {
+            let name = format!("分析结果-{}", Local::now().format("%Y%m%d_%H%M%S"));
+            Ok(Path::new(output_folder).join(name).to_string_lossy().to_string())
+        }
+        OverwritePolicy::Fail => {
+            let inputs = top_level_files(input_folder);
+            let mut conflicts: Vec<String> = snapshot_files(Path::new(output_folder))
+                .into_keys()
+                .filter(|path| !inputs.contains(path))
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            if conflicts.is_empty() {
+                return Ok(output_folder.to_string());
+            }
+            conflicts.sort();
+            Err(AppError::OutputConflict {
+                folder: output_folder.to_string(),
+                files: conflicts,
+            })
+        }
+    }
+}
+
+// 目录顶层的文件（不含子目录中的文件）
+fn top_level_files(dir: &Path) -> HashSet<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else { return HashSet::new() };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+// 写入探测：在输出文件夹中创建并删除一个临时文件，提前发现只读目录
+pub fn ensure_writable(dir: &Path) -> Result<(), AppError> {
+    let probe = Builder::new()
+        .prefix(".battery-analyzer-probe-")
+        .tempfile_in(dir)
+        .map_err(|e| AppError::OutputNotWritable {
+            path: dir.to_string_lossy().to_string(),
+            reason: e.to_string(),
+        })?;
+    probe.close().map_err(|e| AppError::OutputNotWritable {
+        path: dir.to_string_lossy().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+// 输出文件夹所在磁盘空间不足时返回警告信息
+pub fn low_space_warning(dir: &Path) -> Option<String> {
+    let available = available_space(dir)?;
+    (available < LOW_DISK_SPACE_BYTES).then(|| {
+        tr!(
+            "输出文件夹所在磁盘剩余空间仅 {:.1} MB，结果可能无法完整写入",
+            "Only {:.1} MB free on the output folder's disk, results may not be written completely",
+            available as f64 / 1024.0 / 1024.0
+        )
+    })
+}
+
+// 剩余空间少于 required 字节时返回错误；无法获取剩余空间时不检查。目录尚未创建时检查最近的已存在的上级目录
+pub fn check_disk_space(dir: &Path, required: u64) -> Result<(), AppError> {
+    match space_shortfall(dir, required) {
+        Some(available) => Err(AppError::InsufficientDiskSpace {
+            path: dir.to_string_lossy().to_string(),
+            required,
+            available,
+        }),
+        None => Ok(()),
+    }
+}
+
+// 剩余空间少于 required 字节时返回剩余空间
+pub fn space_shortfall(dir: &Path, required: u64) -> Option<u64> {
+    if required == 0 {
+        return None;
+    }
+    let existing = dir.ancestors().find(|dir| dir.is_dir())?;
+    available_space(existing).filter(|&available| available < required)
+}
+
+#[cfg(unix)]
+fn available_space(dir: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn available_space(dir: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path: Vec<u16> = dir.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(path.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    (ok != 0).then_some(available)
+}
+
+// 其他平台暂不检查磁盘空间
+#[cfg(not(any(unix, windows)))]
+fn available_space(_dir: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"data").unwrap();
+    }
+
+    fn conflicts(result: Result<String, AppError>) -> Vec<String> {
+        match result {
+            Err(AppError::OutputConflict { files, .. }) => files,
+            other => panic!("expected OutputConflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn overwrite_keeps_output_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("output").to_string_lossy().to_string();
+        let folder = apply_overwrite_policy(OverwritePolicy::Overwrite, dir.path(), &output).unwrap();
+        assert_eq!(folder, output);
+    }
+
+    #[test]
+    fn rename_uses_timestamped_subfolder() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("output").to_string_lossy().to_string();
+        let folder = apply_overwrite_policy(OverwritePolicy::Rename, dir.path(), &output).unwrap();
+        let folder = Path::new(&folder);
+        assert_eq!(folder.parent().unwrap(), Path::new(&output));
+        assert!(folder.file_name().unwrap().to_string_lossy().starts_with("分析结果-"));
+    }
+
+    #[test]
+    fn fail_allows_empty_output_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("A1.xlsx"));
+        let output = dir.path().join("output").to_string_lossy().to_string();
+        assert!(apply_overwrite_policy(OverwritePolicy::Fail, dir.path(), &output).is_ok());
+    }
+
+    #[test]
+    fn fail_ignores_input_files_when_output_is_input() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("A1.xlsx"));
+        let output = dir.path().to_string_lossy().to_string();
+        assert!(apply_overwrite_policy(OverwritePolicy::Fail, dir.path(), &output).is_ok());
+    }
+
+    #[test]
+    fn fail_reports_results_nested_in_input_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("A1.xlsx"));
+        let previous = dir.path().join("output").join("电池数据汇总表-120000.xlsx");
+        touch(&previous);
+        let output = dir.path().join("output").to_string_lossy().to_string();
+        let files = conflicts(apply_overwrite_policy(OverwritePolicy::Fail, dir.path(), &output));
+        assert_eq!(files, vec![previous.to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn fail_reports_old_logs_when_output_is_input() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("A1.xlsx"));
+        let log = dir.path().join("处理日志-20240101_120000").join("主要处理日志.txt");
+        touch(&log);
+        let output = dir.path().to_string_lossy().to_string();
+        let files = conflicts(apply_overwrite_policy(OverwritePolicy::Fail, dir.path(), &output));
+        assert_eq!(files, vec![log.to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn changed_files_reports_created_and_modified() {
+        let stamp = |size| FileStamp { size, modified: None };
+        let before: Snapshot = [
+            (PathBuf::from("/out/kept.xlsx"), stamp(1)),
+            (PathBuf::from("/out/updated.xlsx"), stamp(1)),
+        ]
+        .into();
+        let after: Snapshot = [
+            (PathBuf::from("/out/kept.xlsx"), stamp(1)),
+            (PathBuf::from("/out/updated.xlsx"), stamp(2)),
+            (PathBuf::from("/out/new.xlsx"), stamp(3)),
+        ]
+        .into();
+        let changed = changed_files(&before, &after);
+        let summary: Vec<(&str, bool)> =
+            changed.iter().map(|file| (file.name.as_str(), file.created)).collect();
+        assert_eq!(summary, vec![("new.xlsx", true), ("updated.xlsx", false)]);
+    }
+
+    #[test]
+    fn removes_created_files_and_empty_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept").join("old.xlsx");
+        touch(&kept);
+        let output = dir.path().join("output");
+        let before = snapshot_files(&output);
+        let dirs_before = snapshot_dirs(&output);
+
+        touch(&output.join("处理日志-20240101_120000").join("主要处理日志.txt"));
+        touch(&dir.path().join("kept").join("new.xlsx"));
+        remove_created_files(&before, &dirs_before, &output);
+        assert!(!output.exists());
+        assert!(kept.exists());
+
+        // 输出文件夹为已有目录时只删除其中新建的内容
+        let output = dir.path().join("kept");
+        let before = snapshot_files(&output);
+        let dirs_before = snapshot_dirs(&output);
+        touch(&output.join("logs").join("log.txt"));
+        let removed = remove_created_files(&before, &dirs_before, &output);
+        assert_eq!(removed.len(), 2);
+        assert!(kept.exists());
+        assert!(!output.join("logs").exists());
+    }
+
+    #[test]
+    fn space_shortfall_skips_zero_requirement() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(space_shortfall(dir.path(), 0), None);
+    }
+}
@@ -0,0 +1,63 @@
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf, Prefix};
+
+// Windows上把绝对路径转换为 \\?\ 扩展长度形式，绕过260字符（MAX_PATH）的限制：
+//   C:\data\cells       -> \\?\C:\data\cells
+//   \\server\share\data -> \\?\UNC\server\share\data
+// 映射的网络驱动器（如 Z:\）按普通盘符处理。扩展形式不会解析 "." 和 ".."，
+// 也不接受 "/"，因此先逐段规范化。相对路径、已是扩展形式的路径以及其他平台的路径原样返回。
+// 目录扫描从转换后的根目录出发，子路径即使超过260字符也能正常读取
+pub fn extended(path: &Path) -> PathBuf {
+    let mut components = path.components();
+    let Some(Component::Prefix(prefix)) = components.next() else {
+        return path.to_path_buf();
+    };
+    let mut extended = OsString::new();
+    match prefix.kind() {
+        Prefix::Disk(letter) => extended.push(format!(r"\\?\{}:", letter as char)),
+        Prefix::UNC(server, share) => {
+            extended.push(r"\\?\UNC\");
+            extended.push(server);
+            extended.push(r"\");
+            extended.push(share);
+        }
+        _ => return path.to_path_buf(),
+    }
+    // "C:data" 这类相对于盘符当前目录的路径无法转换
+    if components.next() != Some(Component::RootDir) {
+        return path.to_path_buf();
+    }
+
+    let mut parts: Vec<&OsStr> = Vec::new();
+    for component in components {
+        match component {
+            Component::Normal(part) => parts.push(part),
+            Component::ParentDir => {
+                parts.pop();
+            }
+            _ => {}
+        }
+    }
+    extended.push(r"\");
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            extended.push(r"\");
+        }
+        extended.push(part);
+    }
+    PathBuf::from(extended)
+}
+
+// 返回给前端的路径去掉扩展前缀，与用户输入的形式保持一致
+pub fn display(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    // 只处理盘符路径，\\?\Volume{...} 等设备路径保持原样
+    let disk = path.strip_prefix(r"\\?\").filter(|rest| rest.get(1..2) == Some(":"));
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = disk {
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::ProcessConfig;
+use crate::error::AppError;
+
+// 预设文件保存在应用配置目录下的 presets 子目录中，每个预设一个JSON文件
+fn presets_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::AppDirUnavailable(e.to_string()))?
+        .join("presets");
+    Ok(dir)
+}
+
+fn preset_path(app: &AppHandle, name: &str) -> Result<PathBuf, AppError> {
+    validate_name(name)?;
+    Ok(presets_dir(app)?.join(format!("{}.json", name)))
+}
+
+// 预设名称直接作为文件名，禁止路径分隔符等非法字符
+fn validate_name(name: &str) -> Result<(), AppError> {
+    let invalid = name.trim().is_empty()
+        || name.starts_with('.')
+        || name
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'));
+    if invalid {
+        return Err(AppError::InvalidPresetName(name.to_string()));
+    }
+    Ok(())
+}
+
+// 保存预设，同名预设直接覆盖
+pub fn save(app: &AppHandle, name: &str, config: &ProcessConfig) -> Result<(), AppError> {
+    let path = preset_path(app, name)?;
+    fs::create_dir_all(presets_dir(app)?).map_err(|e| AppError::io("创建预设目录失败", e))?;
+
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| AppError::io("序列化预设失败", io::Error::from(e)))?;
+    fs::write(&path, json).map_err(|e| AppError::io("保存预设失败", e))
+}
+
+pub fn load(app: &AppHandle, name: &str) -> Result<ProcessConfig, AppError> {
+    let path = preset_path(app, name)?;
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(AppError::PresetNotFound(name.to_string()))
+        }
+        Err(e) => return Err(AppError::io("读取预设失败", e)),
+    };
+    serde_json::from_str(&json).map_err(|e| AppError::io("解析预设失败", io::Error::from(e)))
+}
+
+// 列出所有预设名称（按名称排序）
+pub fn list(app: &AppHandle) -> Result<Vec<String>, AppError> {
+    let dir = presets_dir(app)?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::io("读取预设目录失败", e)),
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+pub fn delete(app: &AppHandle, name: &str) -> Result<(), AppError> {
+    let path = preset_path(app, name)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(AppError::PresetNotFound(name.to_string())),
+        Err(e) => Err(AppError::io("删除预设失败", e)),
+    }
+}
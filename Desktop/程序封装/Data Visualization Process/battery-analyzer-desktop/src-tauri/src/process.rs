@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::process::{Child, Command, ExitStatus};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 轮询子进程状态的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// 正在运行的Python子进程，按任务ID登记，供取消命令使用
+#[derive(Default)]
+pub struct ProcessRegistry {
+    next_id: AtomicU64,
+    children: Mutex<HashMap<u64, Child>>,
+    // 已预留但尚未启动子进程的任务ID（任务队列使用），启动前被取消时从中移除
+    reserved: Mutex<HashSet<u64>>,
+}
+
+// 等待子进程的结果
+pub enum WaitOutcome {
+    Exited(ExitStatus),
+    Cancelled,
+    // 超过时限被终止，附带实际运行时长
+    TimedOut(Duration),
+}
+
+impl ProcessRegistry {
+    // 登记子进程并返回任务ID
+    pub fn register(&self, child: Child) -> u64 {
+        let job_id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        kill_on_exit(&child);
+        self.children.lock().unwrap().insert(job_id, child);
+        job_id
+    }
+
+    // 预留任务ID，使排队中或重试之间的任务在没有子进程时也有可取消的ID
+    pub fn reserve(&self) -> u64 {
+        let job_id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.reserved.lock().unwrap().insert(job_id);
+        job_id
+    }
+
+    // 以预留的ID登记子进程（同一ID可多次登记，用于重试）；
+    // 预留已被取消时立即终止子进程，随后的 wait 返回 Cancelled
+    pub fn register_reserved(&self, job_id: u64, mut child: Child) {
+        kill_on_exit(&child);
+        let mut children = self.children.lock().unwrap();
+        if self.reserved.lock().unwrap().contains(&job_id) {
+            children.insert(job_id, child);
+        } else {
+            drop(children);
+            kill_process_tree(&mut child);
+        }
+    }
+
+    // 预留ID是否仍有效（未被取消）
+    pub fn is_reserved(&self, job_id: u64) -> bool {
+        self.reserved.lock().unwrap().contains(&job_id)
+    }
+
+    // 释放预留ID（任务结束后调用）
+    pub fn release(&self, job_id: u64) {
+        self.reserved.lock().unwrap().remove(&job_id);
+    }
+
+    // 等待子进程结束；每次轮询只短暂持有锁，以便取消命令可以随时介入。
+    // 指定 timeout 时，超时后终止整个进程树
+    pub fn wait(&self, job_id: u64, timeout: Option<Duration>) -> io::Result<WaitOutcome> {
+        let started = Instant::now();
+        loop {
+            {
+                let mut children = self.children.lock().unwrap();
+                let Some(child) = children.get_mut(&job_id) else {
+                    // 子进程已被取消命令移除
+                    return Ok(WaitOutcome::Cancelled);
+                };
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        children.remove(&job_id);
+                        return Ok(WaitOutcome::Exited(status));
+                    }
+                    Ok(None) if timeout.is_some_and(|limit| started.elapsed() >= limit) => {
+                        if let Some(mut child) = children.remove(&job_id) {
+                            kill_process_tree(&mut child);
+                        }
+                        return Ok(WaitOutcome::TimedOut(started.elapsed()));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        children.remove(&job_id);
+                        return Err(e);
+                    }
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    // 取消指定任务；未指定任务ID时取消全部，返回被取消的任务数
+    pub fn cancel(&self, job_id: Option<u64>) -> usize {
+        let (removed, count): (Vec<Child>, usize) = {
+            let mut children = self.children.lock().unwrap();
+            let mut reserved = self.reserved.lock().unwrap();
+            match job_id {
+                Some(id) => {
+                    let child = children.remove(&id);
+                    let was_reserved = reserved.remove(&id);
+                    let count = usize::from(child.is_some() || was_reserved);
+                    (child.into_iter().collect(), count)
+                }
+                None => {
+                    // 运行中的预留任务同时出现在两个集合中，只计一次
+                    let unstarted = reserved.iter().filter(|id| !children.contains_key(id)).count();
+                    reserved.clear();
+                    let removed: Vec<Child> = children.drain().map(|(_, child)| child).collect();
+                    let count = removed.len() + unstarted;
+                    (removed, count)
+                }
+            }
+        };
+
+        for mut child in removed {
+            kill_process_tree(&mut child);
+        }
+        count
+    }
+}
+
+// 子进程被信号终止时返回信号编号（仅Unix）
+pub fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = status;
+        None
+    }
+}
+
+// 让子进程成为独立进程组的组长，便于整组终止
+pub fn isolate_process_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+// Windows下将子进程加入设置了 KILL_ON_JOB_CLOSE 的作业对象：作业句柄随本进程关闭，
+// 应用异常退出时整个Python进程树也会被系统终止。其他平台由退出事件中的 cancel 处理
+#[cfg(windows)]
+fn kill_on_exit(child: &Child) {
+    use std::os::windows::io::AsRawHandle;
+    use std::sync::OnceLock;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    // 作业句柄以整数保存，整个进程生命周期内不关闭
+    static JOB: OnceLock<Option<isize>> = OnceLock::new();
+    let job = JOB.get_or_init(|| unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job.is_null() {
+            return None;
+        }
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        let ok = SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        );
+        (ok != 0).then_some(job as isize)
+    });
+    if let Some(job) = *job {
+        unsafe {
+            AssignProcessToJobObject(job as _, child.as_raw_handle() as _);
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn kill_on_exit(_child: &Child) {}
+
+// 终止子进程及其派生的所有子进程，避免遗留Python工作进程
+fn kill_process_tree(child: &mut Child) {
+    #[cfg(windows)]
+    {
+        // Child::kill 只会结束直接子进程，使用 taskkill /T 结束整个进程树
+        let _ = Command::new("taskkill")
+            .args(["/PID", &child.id().to_string(), "/T", "/F"])
+            .output();
+    }
+    #[cfg(unix)]
+    {
+        // 子进程以自身PID作为进程组ID启动，向整个进程组发送SIGKILL
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+// Python脚本输出进度行的前缀，格式为 "PROGRESS 42/100" 或 "PROGRESS 0.42"
+const PROGRESS_PREFIX: &str = "PROGRESS";
+
+// 进度或耗时不足时数据太少，不估算剩余时间
+const MIN_ETA_PERCENT: f64 = 5.0;
+const MIN_ETA_ELAPSED: Duration = Duration::from_secs(3);
+
+// 进度事件负载
+#[derive(Debug, Clone, Serialize)]
+pub struct Progress {
+    pub job_id: u64,
+    pub processed: Option<u64>,
+    pub total: Option<u64>,
+    // 百分比（0-100）
+    pub percent: f64,
+    // 按目前的处理速度估算的剩余秒数，早期数据不足时为 None
+    pub eta_secs: Option<u64>,
+}
+
+impl Progress {
+    // 根据启动以来的耗时估算剩余时间
+    pub fn with_eta(mut self, elapsed: Duration) -> Self {
+        self.eta_secs = if self.percent >= 100.0 {
+            Some(0)
+        } else if self.percent >= MIN_ETA_PERCENT && elapsed >= MIN_ETA_ELAPSED {
+            let remaining = elapsed.as_secs_f64() * (100.0 - self.percent) / self.percent;
+            Some(remaining.round() as u64)
+        } else {
+            None
+        };
+        self
+    }
+}
+
+// 解析进度行，不符合协议的行返回 None
+pub fn parse_progress(job_id: u64, line: &str) -> Option<Progress> {
+    let rest = line.trim().strip_prefix(PROGRESS_PREFIX)?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let value = rest.trim();
+
+    if let Some((processed, total)) = value.split_once('/') {
+        let processed: u64 = processed.trim().parse().ok()?;
+        let total: u64 = total.trim().parse().ok()?;
+        let percent = if total == 0 {
+            0.0
+        } else {
+            (processed as f64 / total as f64 * 100.0).min(100.0)
+        };
+        return Some(Progress {
+            job_id,
+            processed: Some(processed),
+            total: Some(total),
+            percent,
+            eta_secs: None,
+        });
+    }
+
+    let fraction: f64 = value.parse().ok()?;
+    if !fraction.is_finite() {
+        return None;
+    }
+    Some(Progress {
+        job_id,
+        processed: None,
+        total: None,
+        percent: (fraction * 100.0).clamp(0.0, 100.0),
+        eta_secs: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_count_lines() {
+        let progress = parse_progress(7, "PROGRESS 42/100").unwrap();
+        assert_eq!(progress.job_id, 7);
+        assert_eq!(progress.processed, Some(42));
+        assert_eq!(progress.total, Some(100));
+        assert_eq!(progress.percent, 42.0);
+    }
+
+    #[test]
+    fn parses_fraction_lines() {
+        let progress = parse_progress(1, "  PROGRESS 0.25  ").unwrap();
+        assert_eq!(progress.processed, None);
+        assert_eq!(progress.total, None);
+        assert_eq!(progress.percent, 25.0);
+    }
+
+    #[test]
+    fn clamps_percent() {
+        assert_eq!(parse_progress(1, "PROGRESS 150/100").unwrap().percent, 100.0);
+        assert_eq!(parse_progress(1, "PROGRESS 3/0").unwrap().percent, 0.0);
+        assert_eq!(parse_progress(1, "PROGRESS 1.5").unwrap().percent, 100.0);
+        assert_eq!(parse_progress(1, "PROGRESS -0.5").unwrap().percent, 0.0);
+    }
+
+    #[test]
+    fn ignores_other_lines() {
+        for line in [
+            "处理A1系列: 50%",
+            "PROGRESS",
+            "PROGRESS42/100",
+            "progress 1/2",
+            "PROGRESS a/b",
+            "PROGRESS NaN",
+            "PROGRESS inf",
+        ] {
+            assert!(parse_progress(1, line).is_none(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn estimates_remaining_time() {
+        let progress = parse_progress(1, "PROGRESS 50/100").unwrap();
+        assert_eq!(progress.clone().with_eta(Duration::from_secs(10)).eta_secs, Some(10));
+        // 耗时太短
+        assert_eq!(progress.with_eta(Duration::from_secs(1)).eta_secs, None);
+        // 进度太少
+        let early = parse_progress(1, "PROGRESS 1/100").unwrap();
+        assert_eq!(early.with_eta(Duration::from_secs(60)).eta_secs, None);
+        let done = parse_progress(1, "PROGRESS 100/100").unwrap();
+        assert_eq!(done.with_eta(Duration::ZERO).eta_secs, Some(0));
+    }
+}
@@ -0,0 +1,208 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// 未指定解释器时依次尝试的名称
+const DEFAULT_CANDIDATES: &[&str] = &["python", "python3"];
+
+// 查找Python解释器失败的原因
+#[derive(Debug)]
+pub enum PythonLookupError {
+    // 所有候选名称都无法在PATH中找到
+    NotFound { tried: Vec<String> },
+    // 指定的路径存在但不是可执行文件
+    NotExecutable(PathBuf),
+    // 虚拟环境/conda环境目录中没有Python解释器
+    EnvMissingPython(PathBuf),
+    // 无法定位指定名称的conda环境
+    CondaEnvNotFound(String),
+}
+
+// 解析后的Python运行环境：解释器路径及激活环境所需的环境变量
+#[derive(Debug)]
+pub struct PythonRuntime {
+    pub executable: PathBuf,
+    pub envs: Vec<(&'static str, OsString)>,
+}
+
+impl PythonRuntime {
+    // 创建以该解释器启动、并已激活对应环境的命令
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.executable);
+        cmd.envs(self.envs.iter().map(|(key, value)| (key, value)));
+        cmd
+    }
+}
+
+// 解析Python运行环境，优先级：venv_path > conda_env > python_executable
+pub fn resolve_runtime(
+    python_executable: Option<&str>,
+    venv_path: Option<&str>,
+    conda_env: Option<&str>,
+) -> Result<PythonRuntime, PythonLookupError> {
+    if let Some(venv) = non_empty(venv_path) {
+        return venv_runtime(Path::new(venv));
+    }
+    if let Some(env_name) = non_empty(conda_env) {
+        return conda_runtime(env_name);
+    }
+    Ok(PythonRuntime {
+        executable: resolve_python(python_executable)?,
+        envs: Vec::new(),
+    })
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|s| !s.is_empty())
+}
+
+// venv：解释器位于 Scripts/python.exe（Windows）或 bin/python
+fn venv_runtime(venv: &Path) -> Result<PythonRuntime, PythonLookupError> {
+    let bin_dir = if cfg!(windows) { venv.join("Scripts") } else { venv.join("bin") };
+    let executable = bin_dir.join(if cfg!(windows) { "python.exe" } else { "python" });
+    if !is_executable(&executable) {
+        return Err(PythonLookupError::EnvMissingPython(venv.to_path_buf()));
+    }
+
+    let mut envs = vec![("VIRTUAL_ENV", venv.as_os_str().to_owned())];
+    envs.extend(prepend_path(&[bin_dir]));
+    Ok(PythonRuntime { executable, envs })
+}
+
+// conda：接受环境目录或环境名称，解释器位于 python.exe（Windows）或 bin/python
+fn conda_runtime(env: &str) -> Result<PythonRuntime, PythonLookupError> {
+    let prefix = conda_prefix(env).ok_or_else(|| PythonLookupError::CondaEnvNotFound(env.to_string()))?;
+
+    let (executable, path_dirs) = if cfg!(windows) {
+        (
+            prefix.join("python.exe"),
+            vec![prefix.clone(), prefix.join("Library").join("bin"), prefix.join("Scripts")],
+        )
+    } else {
+        (prefix.join("bin").join("python"), vec![prefix.join("bin")])
+    };
+    if !is_executable(&executable) {
+        return Err(PythonLookupError::EnvMissingPython(prefix));
+    }
+
+    let env_name = prefix
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| OsString::from(env));
+    let mut envs = vec![
+        ("CONDA_PREFIX", prefix.as_os_str().to_owned()),
+        ("CONDA_DEFAULT_ENV", env_name),
+    ];
+    envs.extend(prepend_path(&path_dirs));
+    Ok(PythonRuntime { executable, envs })
+}
+
+// 定位conda环境目录：已存在的目录直接使用，否则在conda安装目录的 envs 下按名称查找
+fn conda_prefix(env: &str) -> Option<PathBuf> {
+    let as_path = Path::new(env);
+    if as_path.is_dir() {
+        return Some(as_path.to_path_buf());
+    }
+
+    let base = conda_base()?;
+    let prefix = if env == "base" { base } else { base.join("envs").join(env) };
+    prefix.is_dir().then_some(prefix)
+}
+
+// conda安装目录：CONDA_EXE 位于 <base>/bin/conda 或 <base>\Scripts\conda.exe
+fn conda_base() -> Option<PathBuf> {
+    if let Some(conda_exe) = env::var_os("CONDA_EXE") {
+        let base = Path::new(&conda_exe).parent()?.parent()?;
+        return Some(base.to_path_buf());
+    }
+
+    let home = env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" })?;
+    ["miniconda3", "anaconda3", "miniforge3"]
+        .iter()
+        .map(|dir| Path::new(&home).join(dir))
+        .find(|dir| dir.is_dir())
+}
+
+// 将目录加到PATH最前面
+fn prepend_path(dirs: &[PathBuf]) -> Option<(&'static str, OsString)> {
+    let current = env::var_os("PATH").unwrap_or_default();
+    let paths = dirs.iter().cloned().chain(env::split_paths(&current));
+    env::join_paths(paths).ok().map(|joined| ("PATH", joined))
+}
+
+// 解析要使用的Python解释器：显式配置优先，未配置时依次尝试 python、python3
+pub fn resolve_python(configured: Option<&str>) -> Result<PathBuf, PythonLookupError> {
+    match configured.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(executable) => resolve_executable(executable),
+        None => DEFAULT_CANDIDATES
+            .iter()
+            .find_map(|name| find_in_path(name))
+            .ok_or_else(|| PythonLookupError::NotFound {
+                tried: DEFAULT_CANDIDATES.iter().map(|s| s.to_string()).collect(),
+            }),
+    }
+}
+
+fn resolve_executable(executable: &str) -> Result<PathBuf, PythonLookupError> {
+    let path = Path::new(executable);
+
+    // 仅有名称时在PATH中查找，带目录时直接校验该路径
+    if path.components().count() == 1 {
+        return find_in_path(executable).ok_or_else(|| PythonLookupError::NotFound {
+            tried: vec![executable.to_string()],
+        });
+    }
+
+    if !path.exists() {
+        return Err(PythonLookupError::NotFound {
+            tried: vec![executable.to_string()],
+        });
+    }
+    if !is_executable(path) {
+        return Err(PythonLookupError::NotExecutable(path.to_path_buf()));
+    }
+    Ok(path.to_path_buf())
+}
+
+// 在PATH中查找可执行文件（Windows下同时尝试PATHEXT中的扩展名）
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path_var = env::var_os("PATH")?;
+    env::split_paths(&path_var).find_map(|dir| {
+        executable_names(name)
+            .into_iter()
+            .map(|candidate| dir.join(candidate))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+#[cfg(windows)]
+fn executable_names(name: &str) -> Vec<String> {
+    let pathext = env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.BAT;.CMD".to_string());
+    let mut names = vec![name.to_string()];
+    names.extend(
+        pathext
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| format!("{}{}", name, ext.to_lowercase())),
+    );
+    names
+}
+
+#[cfg(not(windows))]
+fn executable_names(name: &str) -> Vec<String> {
+    vec![name.to_string()]
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,351 @@
+use std::sync::Mutex;
+use std::thread;
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::{ProcessConfig, QueueOrder};
+use crate::error::AppError;
+use crate::estimate;
+use crate::process::ProcessRegistry;
+use crate::runner::{self, ProcessOutcome, ProcessResult};
+
+// 队列任务状态事件名
+const JOB_STATUS_EVENT: &str = "battery://job-status";
+// 队列暂停/继续事件名
+const QUEUE_STATUS_EVENT: &str = "battery://queue-status";
+// 最多保留的已结束任务数，超出时移除最早入队的已结束任务
+const MAX_FINISHED_JOBS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+// 队列中的任务概要
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub job_id: u64,
+    pub input_folder: String,
+    pub status: JobStatus,
+    // 入队时间（UTC，RFC-3339格式）
+    pub enqueued_at: String,
+}
+
+// 队列状态：暂停时排队中的任务不会开始（运行中的任务照常完成）
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueInfo {
+    pub paused: bool,
+    pub concurrency: usize,
+    pub jobs: Vec<JobInfo>,
+}
+
+// 队列暂停/继续事件负载
+#[derive(Debug, Clone, Serialize)]
+struct QueueStatusEvent {
+    paused: bool,
+}
+
+// 任务状态事件负载，任务结束时附带处理结果
+#[derive(Debug, Clone, Serialize)]
+struct JobStatusEvent<'a> {
+    #[serde(flatten)]
+    job: &'a JobInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<&'a ProcessOutcome>,
+}
+
+struct Job {
+    info: JobInfo,
+    // 开始运行时取出
+    config: Option<ProcessConfig>,
+    cancel_requested: bool,
+    order: QueueOrder,
+    // 输入文件总大小，仅在 order 不为 AsIs 时计算（否则为0）
+    cost: u64,
+}
+
+struct QueueState {
+    jobs: Vec<Job>,
+    workers: usize,
+    concurrency: usize,
+    paused: bool,
+}
+
+impl QueueState {
+    // 已结束的任务超过 MAX_FINISHED_JOBS 时移除最早的，排队中和运行中的任务不受影响
+    fn evict_finished(&mut self) {
+        let finished = self.jobs.iter().filter(|job| job.info.status.is_finished()).count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|job| {
+            if excess > 0 && job.info.status.is_finished() {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+    }
+}
+
+// 按入队顺序执行分析任务的队列，同时运行的任务数不超过 concurrency（默认1）
+pub struct JobQueue {
+    state: Mutex<QueueState>,
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        JobQueue {
+            state: Mutex::new(QueueState {
+                jobs: Vec::new(),
+                workers: 0,
+                concurrency: 1,
+                paused: false,
+            }),
+        }
+    }
+}
+
+impl JobQueue {
+    // 加入队列并返回任务ID；有空闲名额且队列未暂停时立即启动工作线程。
+    // order 不为 AsIs 时需要扫描输入文件夹计算成本，不应在主线程调用
+    pub fn enqueue(&self, app: &AppHandle, config: ProcessConfig) -> u64 {
+        let job_id = app.state::<ProcessRegistry>().reserve();
+        let info = JobInfo {
+            job_id,
+            input_folder: config.input_folder.clone(),
+            status: JobStatus::Queued,
+            enqueued_at: Utc::now().to_rfc3339(),
+        };
+        let order = config.queue_order;
+        let cost = if order == QueueOrder::AsIs { 0 } else { estimate::job_cost(&config) };
+
+        // 先入队再发送排队状态，持有锁期间工作线程无法取出该任务，排队状态总在运行状态之前
+        let mut state = self.state.lock().unwrap();
+        state.evict_finished();
+        emit_status(app, &info, None);
+        state.jobs.push(Job {
+            info,
+            config: Some(config),
+            cancel_requested: false,
+            order,
+            cost,
+        });
+        self.spawn_workers(app, &mut state);
+        job_id
+    }
+
+    pub fn list(&self) -> QueueInfo {
+        let state = self.state.lock().unwrap();
+        QueueInfo {
+            paused: state.paused,
+            concurrency: state.concurrency,
+            jobs: state.jobs.iter().map(|job| job.info.clone()).collect(),
+        }
+    }
+
+    // 暂停队列：不再开始排队中的任务，运行中的任务照常完成。已暂停时返回 false
+    pub fn pause(&self, app: &AppHandle) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.paused {
+            return false;
+        }
+        state.paused = true;
+        drop(state);
+        emit_queue_status(app, true);
+        true
+    }
+
+    // 继续处理排队中的任务。未暂停时返回 false
+    pub fn resume(&self, app: &AppHandle) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if !state.paused {
+            return false;
+        }
+        state.paused = false;
+        self.spawn_workers(app, &mut state);
+        drop(state);
+        emit_queue_status(app, false);
+        true
+    }
+
+    // 取消任务：排队中的直接标记为已取消，运行中的终止其Python进程。
+    // 任务不存在或已结束时返回 false
+    pub fn cancel(&self, app: &AppHandle, job_id: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(job) = state.jobs.iter_mut().find(|job| job.info.job_id == job_id) else {
+            return false;
+        };
+        match job.info.status {
+            JobStatus::Queued => {
+                job.info.status = JobStatus::Cancelled;
+                job.config = None;
+                let info = job.info.clone();
+                drop(state);
+                app.state::<ProcessRegistry>().release(job_id);
+                emit_status(app, &info, None);
+                true
+            }
+            JobStatus::Running => {
+                job.cancel_requested = true;
+                drop(state);
+                app.state::<ProcessRegistry>().cancel(Some(job_id));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // 取消全部任务：排队中的标记为已取消，并终止所有Python子进程（包括不在队列中的运行），
+    // 运行中的任务结束后由工作线程发送已取消状态。返回被取消的任务数，没有任务时为0
+    pub fn cancel_all(&self, app: &AppHandle) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let mut cancelled = Vec::new();
+        for job in &mut state.jobs {
+            match job.info.status {
+                JobStatus::Queued => {
+                    job.info.status = JobStatus::Cancelled;
+                    job.config = None;
+                    cancelled.push(job.info.clone());
+                }
+                JobStatus::Running => job.cancel_requested = true,
+                _ => {}
+            }
+        }
+        drop(state);
+
+        // 同时清除排队任务预留的ID
+        let count = app.state::<ProcessRegistry>().cancel(None);
+        for info in &cancelled {
+            emit_status(app, info, None);
+        }
+        count
+    }
+
+    // 设置同时运行的任务数（至少为1），调高时立即启动更多工作线程
+    pub fn set_concurrency(&self, app: &AppHandle, concurrency: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.concurrency = concurrency.max(1);
+        self.spawn_workers(app, &mut state);
+    }
+
+    fn spawn_workers(&self, app: &AppHandle, state: &mut QueueState) {
+        if state.paused {
+            return;
+        }
+        let queued = state
+            .jobs
+            .iter()
+            .filter(|job| job.info.status == JobStatus::Queued)
+            .count();
+        let wanted = state.concurrency.min(state.workers + queued);
+        while state.workers < wanted {
+            state.workers += 1;
+            let app = app.clone();
+            thread::spawn(move || worker(&app));
+        }
+    }
+
+    // 取出下一个排队任务并标记为运行中；没有任务或队列已暂停时工作线程退出（继续时重新启动），
+    // 工作线程计数由 WorkerGuard 在退出时减少。
+    // 最早入队的排队任务的 queue_order 决定本次在所有排队任务中如何选取，成本相同时按入队顺序
+    fn next_job(&self) -> Option<(JobInfo, ProcessConfig)> {
+        let mut state = self.state.lock().unwrap();
+        if state.paused {
+            return None;
+        }
+        let mut queued: Vec<&mut Job> = state
+            .jobs
+            .iter_mut()
+            .filter(|job| job.info.status == JobStatus::Queued)
+            .collect();
+        if let Some(order) = queued.first().map(|job| job.order) {
+            order.sort_by_cost(&mut queued, |job| job.cost);
+        }
+        queued.into_iter().next().and_then(|job| {
+            let config = job.config.take()?;
+            job.info.status = JobStatus::Running;
+            Some((job.info.clone(), config))
+        })
+    }
+
+    fn finish(&self, job_id: u64, success: bool, cancelled: bool) -> Option<JobInfo> {
+        let mut state = self.state.lock().unwrap();
+        let job = state.jobs.iter_mut().find(|job| job.info.job_id == job_id)?;
+        job.info.status = if job.cancel_requested || cancelled {
+            JobStatus::Cancelled
+        } else if success {
+            JobStatus::Done
+        } else {
+            JobStatus::Failed
+        };
+        let info = job.info.clone();
+        state.evict_finished();
+        Some(info)
+    }
+}
+
+// 工作线程退出时（包括任务panic）减少工作线程计数，并在仍有排队任务时补充工作线程。
+// panic时运行中的任务标记为失败，避免一直显示为运行中
+struct WorkerGuard<'a> {
+    app: &'a AppHandle,
+    queue: &'a JobQueue,
+    // 运行中的任务，正常结束后清除
+    running: Option<u64>,
+}
+
+impl Drop for WorkerGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(job_id) = self.running.take() {
+            self.app.state::<ProcessRegistry>().release(job_id);
+            if let Some(info) = self.queue.finish(job_id, false, false) {
+                emit_status(self.app, &info, None);
+            }
+        }
+        if let Ok(mut state) = self.queue.state.lock() {
+            state.workers -= 1;
+            self.queue.spawn_workers(self.app, &mut state);
+        }
+    }
+}
+
+fn worker(app: &AppHandle) {
+    let queue = app.state::<JobQueue>();
+    let mut guard = WorkerGuard { app, queue: &queue, running: None };
+    while let Some((info, config)) = queue.next_job() {
+        guard.running = Some(info.job_id);
+        emit_status(app, &info, None);
+
+        let input_folder = config.input_folder.clone();
+        let result = runner::execute_with_id(app, config, Some(info.job_id));
+        // cancel_processing 也可能终止队列中的任务
+        let cancelled = matches!(result, Err(AppError::Cancelled));
+        let result = result
+            .unwrap_or_else(|e| ProcessOutcome::Run(ProcessResult::from_error(input_folder, e)));
+        // 任务结束后释放预留的ID
+        guard.running = None;
+        app.state::<ProcessRegistry>().release(info.job_id);
+
+        if let Some(info) = queue.finish(info.job_id, result.success(), cancelled) {
+            emit_status(app, &info, Some(&result));
+        }
+    }
+}
+
+fn emit_status(app: &AppHandle, job: &JobInfo, result: Option<&ProcessOutcome>) {
+    let _ = app.emit(JOB_STATUS_EVENT, JobStatusEvent { job, result });
+}
+
+fn emit_queue_status(app: &AppHandle, paused: bool) {
+    let _ = app.emit(QUEUE_STATUS_EVENT, QueueStatusEvent { paused });
+}
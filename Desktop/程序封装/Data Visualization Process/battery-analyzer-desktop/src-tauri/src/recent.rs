@@ -0,0 +1,75 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppError;
+
+// 最多保留的最近使用文件夹数量
+const MAX_RECENT: usize = 10;
+
+// 串行化读-改-写，避免并发命令互相覆盖列表
+static LOCK: Mutex<()> = Mutex::new(());
+
+// 最近使用的文件夹保存在应用配置目录下的 recent_folders.json 中
+fn recent_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::AppDirUnavailable(e.to_string()))?;
+    Ok(dir.join("recent_folders.json"))
+}
+
+fn read_list(path: &PathBuf) -> Result<Vec<String>, AppError> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::io("读取最近使用的文件夹失败", e)),
+    };
+    // 文件损坏时从空列表重新开始
+    Ok(serde_json::from_str(&json).unwrap_or_default())
+}
+
+// 最近使用的文件夹，最新的在前
+pub fn list(app: &AppHandle) -> Result<Vec<String>, AppError> {
+    let _guard = LOCK.lock().unwrap();
+    read_list(&recent_path(app)?)
+}
+
+// 将文件夹移到列表最前面，超出上限的旧记录被丢弃
+pub fn record(app: &AppHandle, folder: &str) -> Result<(), AppError> {
+    if folder.trim().is_empty() {
+        return Ok(());
+    }
+    let _guard = LOCK.lock().unwrap();
+    let path = recent_path(app)?;
+    let mut folders = read_list(&path)?;
+    folders.retain(|existing| existing != folder);
+    folders.insert(0, folder.to_string());
+    folders.truncate(MAX_RECENT);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| AppError::io("创建配置目录失败", e))?;
+    }
+    let json = serde_json::to_string_pretty(&folders)
+        .map_err(|e| AppError::io("序列化最近使用的文件夹失败", io::Error::from(e)))?;
+    fs::write(&path, json).map_err(|e| AppError::io("保存最近使用的文件夹失败", e))
+}
+
+pub fn clear(app: &AppHandle) -> Result<(), AppError> {
+    let _guard = LOCK.lock().unwrap();
+    match fs::remove_file(recent_path(app)?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::io("清除最近使用的文件夹失败", e)),
+    }
+}
+
+// 记录失败不影响主流程，只写日志
+pub fn record_quietly(app: &AppHandle, folder: &str) {
+    if let Err(e) = record(app, folder) {
+        log::warn!("记录最近使用的文件夹失败: {}", e);
+    }
+}
@@ -0,0 +1,253 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::config;
+use crate::error::AppError;
+use crate::excel;
+use crate::i18n::tr;
+use crate::report::{self, Table};
+use crate::scan::{self, ScanOptions};
+
+// 未指定方法时使用的方法（与 modules/config_parser.py 的默认值一致）
+const DEFAULT_METHOD: &str = "retention_curve_mse";
+// PCA和保留率曲线方法需要的最少通道数，不足时退回传统方法（见 modules/reference_channel.py）
+const MIN_CHANNELS: usize = 3;
+const PCA_COMPONENTS: usize = 2;
+const POWER_ITERATIONS: usize = 200;
+// 加权MSE的指数权重：越靠后的循环权重越高，最后30%的循环再乘以强调系数（见 modules/capacity_retention.py）
+const WEIGHT_FACTOR: f64 = 0.9;
+const LATE_CYCLES_START: f64 = 0.7;
+const LATE_CYCLES_EMPHASIS: f64 = 2.0;
+
+const CHARGE_COLUMN: &str = "充电比容量(mAh/g)";
+const DISCHARGE_COLUMN: &str = "放电比容量(mAh/g)";
+
+// 单个通道（数据文件）的评分，score 的含义随方法不同：
+// traditional 为首效（%，越高越好），pca 为到主成分中心的距离，retention_curve_mse 为与批次平均曲线的加权MSE（均越小越好）
+#[derive(Debug, Serialize)]
+pub struct ChannelScore {
+    pub file: String,
+    pub score: Option<f64>,
+    // 无法读取或数据不足时的原因
+    pub error: Option<String>,
+}
+
+// 参考通道预览结果
+#[derive(Debug, Serialize)]
+pub struct ReferencePreview {
+    // 实际使用的方法；通道数不足时为 traditional
+    pub method: String,
+    pub requested_method: String,
+    // 被选为参考通道的文件名及其在 channels 中的下标，没有可用数据时为 None
+    pub reference: Option<String>,
+    pub index: Option<usize>,
+    pub channels: Vec<ChannelScore>,
+}
+
+// 读取文件夹中同一批次的数据文件，按指定方法预估将被选为参考通道的文件。
+// 计算方式与 modules/reference_channel.py 一致，结果仅供运行前确认，以Python的输出为准
+pub fn preview_reference_channel(folder: &str, method: Option<&str>) -> Result<ReferencePreview, AppError> {
+    let requested = method.filter(|m| !m.is_empty()).unwrap_or(DEFAULT_METHOD);
+    if let Some(error) = config::check_reference_channel_method(requested) {
+        return Err(AppError::InvalidConfig(vec![error]));
+    }
+    scan::check_directory(folder)?;
+    let dir = Path::new(folder);
+
+    let mut files = scan::scan_directory(dir, &ScanOptions::default())?;
+    files.retain(|file| file.is_excel);
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    let curves: Vec<Result<Curve, String>> = files.iter().map(|file| read_curve(&file.path)).collect();
+
+    let usable = curves.iter().filter(|curve| curve.is_ok()).count();
+    let method = if requested != "traditional" && usable < MIN_CHANNELS {
+        "traditional"
+    } else {
+        requested
+    };
+    let scores = match method {
+        "pca" => pca_scores(&curves),
+        "retention_curve_mse" => mse_scores(&curves),
+        _ => curves
+            .iter()
+            .map(|curve| curve.as_ref().ok().and_then(Curve::first_efficiency))
+            .collect(),
+    };
+
+    // 传统方法取首效最高的通道，其余方法取评分最小的通道
+    let higher_is_better = method == "traditional";
+    let index = scores
+        .iter()
+        .enumerate()
+        .filter_map(|(i, score)| score.map(|score| (i, score)))
+        .max_by(|(_, a), (_, b)| {
+            let ordering = a.total_cmp(b);
+            if higher_is_better {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        })
+        .map(|(i, _)| i);
+
+    let channels = files
+        .iter()
+        .zip(curves)
+        .zip(scores)
+        .map(|((file, curve), score)| ChannelScore {
+            file: file.name.clone(),
+            score,
+            error: curve.err(),
+        })
+        .collect();
+    Ok(ReferencePreview {
+        method: method.to_string(),
+        requested_method: requested.to_string(),
+        reference: index.map(|i| files[i].name.clone()),
+        index,
+        channels,
+    })
+}
+
+// 一个通道的循环数据
+struct Curve {
+    charge: Vec<f64>,
+    discharge: Vec<f64>,
+}
+
+impl Curve {
+    // 首周库伦效率（%）
+    fn first_efficiency(&self) -> Option<f64> {
+        let (charge, discharge) = (*self.charge.first()?, *self.discharge.first()?);
+        (charge > 0.0 && discharge > 0.0).then(|| discharge / charge * 100.0)
+    }
+}
+
+fn read_curve(path: &str) -> Result<Curve, String> {
+    let mut workbook = excel::open_workbook(path).map_err(|e| e.to_string())?;
+    let (_, range) = excel::read_sheet(&mut workbook, path, Some(excel::DEFAULT_CYCLE_SHEET))
+        .map_err(|e| e.to_string())?;
+    let table = Table::from_range(&range);
+    let column = |name: &str| -> Result<Vec<f64>, String> {
+        let index = table
+            .column(name)
+            .ok_or_else(|| tr!("缺少列: {}", "Missing column: {}", name))?;
+        Ok(table
+            .rows
+            .iter()
+            .map_while(|row| row.get(index).and_then(report::as_number))
+            .collect())
+    };
+    let curve = Curve { charge: column(CHARGE_COLUMN)?, discharge: column(DISCHARGE_COLUMN)? };
+    if curve.discharge.is_empty() {
+        return Err(tr!("{} 列没有数据", "Column {} has no data", DISCHARGE_COLUMN));
+    }
+    Ok(curve)
+}
+
+// 截取到所有可用通道共同的循环数；不足2个循环时返回 None
+fn truncated(curves: &[Result<Curve, String>]) -> Option<(Vec<usize>, Vec<&[f64]>)> {
+    let (indexes, data): (Vec<usize>, Vec<&[f64]>) = curves
+        .iter()
+        .enumerate()
+        .filter_map(|(i, curve)| curve.as_ref().ok().map(|curve| (i, curve.discharge.as_slice())))
+        .unzip();
+    let min_cycles = data.iter().map(|curve| curve.len()).min()?;
+    if min_cycles < 2 {
+        return None;
+    }
+    Some((indexes, data.into_iter().map(|curve| &curve[..min_cycles]).collect()))
+}
+
+// 每个循环在通道间标准化后做主成分分析，返回各通道在前两个主成分上到中心的距离
+fn pca_scores(curves: &[Result<Curve, String>]) -> Vec<Option<f64>> {
+    let mut scores = vec![None; curves.len()];
+    let Some((indexes, data)) = truncated(curves) else { return scores };
+    let n = data.len();
+    let cycles = data[0].len();
+
+    let mut x = vec![vec![0.0; cycles]; n];
+    for c in 0..cycles {
+        let mean = data.iter().map(|curve| curve[c]).sum::<f64>() / n as f64;
+        let variance = data.iter().map(|curve| (curve[c] - mean).powi(2)).sum::<f64>() / n as f64;
+        let std = variance.sqrt();
+        for (row, curve) in x.iter_mut().zip(&data) {
+            row[c] = if std > 0.0 { (curve[c] - mean) / std } else { 0.0 };
+        }
+    }
+
+    // 主成分得分 = U·Σ，由 X·Xᵀ 的特征分解得到：得分平方和 = Σ λ·u²
+    let mut gram: Vec<Vec<f64>> = x
+        .iter()
+        .map(|a| x.iter().map(|b| a.iter().zip(b).map(|(p, q)| p * q).sum()).collect())
+        .collect();
+    let mut distance = vec![0.0; n];
+    for _ in 0..PCA_COMPONENTS.min(n).min(cycles) {
+        let Some((lambda, u)) = top_eigen(&gram) else { break };
+        for (d, value) in distance.iter_mut().zip(&u) {
+            *d += lambda * value * value;
+        }
+        for (i, row) in gram.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell -= lambda * u[i] * u[j];
+            }
+        }
+    }
+    for (i, d) in indexes.into_iter().zip(distance) {
+        scores[i] = Some(d.sqrt());
+    }
+    scores
+}
+
+// 幂迭代求对称半正定矩阵的最大特征值及特征向量
+fn top_eigen(matrix: &[Vec<f64>]) -> Option<(f64, Vec<f64>)> {
+    let n = matrix.len();
+    // 起始向量避免与特征向量正交
+    let mut v: Vec<f64> = (0..n).map(|i| 1.0 + i as f64 / n as f64).collect();
+    let mut lambda = 0.0;
+    for _ in 0..POWER_ITERATIONS {
+        let w: Vec<f64> = matrix.iter().map(|row| row.iter().zip(&v).map(|(a, b)| a * b).sum()).collect();
+        let norm = w.iter().map(|value| value * value).sum::<f64>().sqrt();
+        if norm <= f64::EPSILON {
+            return None;
+        }
+        lambda = norm;
+        v = w.into_iter().map(|value| value / norm).collect();
+    }
+    Some((lambda, v))
+}
+
+// 各通道放电容量曲线与批次平均曲线的加权MSE
+fn mse_scores(curves: &[Result<Curve, String>]) -> Vec<Option<f64>> {
+    let mut scores = vec![None; curves.len()];
+    let Some((indexes, data)) = truncated(curves) else { return scores };
+    let cycles = data[0].len();
+    let average: Vec<f64> = (0..cycles)
+        .map(|c| data.iter().map(|curve| curve[c]).sum::<f64>() / data.len() as f64)
+        .collect();
+
+    let late_start = (cycles as f64 * LATE_CYCLES_START) as usize;
+    let weights: Vec<f64> = (0..cycles)
+        .map(|i| {
+            let weight = WEIGHT_FACTOR.powi((cycles - i - 1) as i32);
+            if i >= late_start {
+                weight * LATE_CYCLES_EMPHASIS
+            } else {
+                weight
+            }
+        })
+        .collect();
+    let total_weight: f64 = weights.iter().sum();
+
+    for (i, curve) in indexes.into_iter().zip(data) {
+        let weighted: f64 = curve
+            .iter()
+            .zip(&average)
+            .zip(&weights)
+            .map(|((value, mean), weight)| weight * (value - mean).powi(2))
+            .sum();
+        scores[i] = Some(weighted / total_weight);
+    }
+    scores
+}
@@ -0,0 +1,438 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use calamine::{Data, Range};
+use chrono::Local;
+
+use crate::error::AppError;
+use crate::excel;
+use crate::i18n::{self, tr, Locale};
+use crate::{manifest, scan};
+
+// Python分析脚本输出的汇总工作簿（见 main_processor.py 的 _export_results），
+// 未指定 output_name_template 时文件名为 电池数据汇总表-{开始时间}.xlsx
+pub const RESULT_PREFIX: &str = "电池数据汇总表-";
+// 汇总工作簿中的工作表
+pub const MAIN_SHEET: &str = "主数据";
+pub const STATISTICS_SHEET: &str = "统计数据";
+pub const ANOMALY_SHEET: &str = "异常数据";
+
+// 报告中依次显示的工作表，依次为 (工作表名, 中文标题, 英文标题)
+const RESULT_SHEETS: &[(&str, &str, &str)] = &[
+    (STATISTICS_SHEET, "统计数据（剔除异常点）", "Statistics (outliers removed)"),
+    (MAIN_SHEET, "主数据", "Main data"),
+    (ANOMALY_SHEET, "异常数据", "Anomalous data"),
+];
+
+// 每个表格最多显示的行数，完整数据仍以xlsx为准
+const MAX_TABLE_ROWS: usize = 200;
+
+// 柱状图使用的标签列和数值列（按优先级）
+const CHART_LABEL_COLUMNS: &[&str] = &["批次", "系列"];
+const CHART_VALUE_COLUMN: &str = "首放平均值";
+
+const STYLE: &str = "body{font-family:-apple-system,'Segoe UI','Microsoft YaHei',sans-serif;margin:24px;color:#222}\
+h1{font-size:22px}h2{font-size:18px;margin-top:32px;border-bottom:1px solid #ddd;padding-bottom:4px}\
+table{border-collapse:collapse;font-size:12px;margin:8px 0}th,td{border:1px solid #ccc;padding:3px 6px;text-align:left}\
+th{background:#f3f5f7}tr:nth-child(even) td{background:#fafafa}.note{color:#888;font-size:12px}\
+.missing{color:#b45309}.wrap{overflow-x:auto}";
+
+// 读取结果文件夹中的汇总工作簿（见 result_workbook），生成可单独分发的HTML报告（内联样式和SVG图表），返回报告路径。
+// 缺少工作簿或工作表时在报告中注明，不视为错误。output_html 未指定时写入结果文件夹
+pub fn generate_report(result_dir: &str, output_html: Option<&str>) -> Result<String, AppError> {
+    scan::check_directory(result_dir)?;
+    let dir = Path::new(result_dir);
+
+    let generated_at = Local::now();
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+         <title>{}</title><style>{}</style></head><body>",
+        tr!("电池数据分析报告", "Battery analysis report"),
+        STYLE
+    );
+    let _ = write!(
+        html,
+        "<h1>{}</h1><p class=\"note\">{}: {}<br>{}: {}</p>",
+        tr!("电池数据分析报告", "Battery analysis report"),
+        tr!("结果文件夹", "Result folder"),
+        escape(result_dir),
+        tr!("生成时间", "Generated at"),
+        generated_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    match result_workbook(dir) {
+        Some(path) => render_results(&mut html, &path),
+        None => missing(
+            &mut html,
+            &tr!(
+                "未找到分析结果文件（{}*.xlsx）",
+                "No analysis result file found ({}*.xlsx)",
+                RESULT_PREFIX
+            ),
+        ),
+    }
+    html.push_str("</body></html>");
+
+    let output = match output_html.filter(|p| !p.is_empty()) {
+        Some(output) => PathBuf::from(output),
+        None => dir.join(format!("分析报告_{}.html", generated_at.format("%Y%m%d_%H%M%S"))),
+    };
+    fs::write(&output, html).map_err(|e| AppError::io("写入报告失败", e))?;
+    Ok(output.to_string_lossy().to_string())
+}
+
+fn render_results(html: &mut String, path: &Path) {
+    let path_str = path.to_string_lossy().to_string();
+    let _ = write!(
+        html,
+        "<p class=\"note\">{}: {}</p>",
+        tr!("数据来源", "Source"),
+        escape(&file_name(path))
+    );
+    let mut workbook = match excel::open_workbook(&path_str) {
+        Ok(workbook) => workbook,
+        Err(e) => return missing(html, &e.to_string()),
+    };
+
+    for (sheet, zh, en) in RESULT_SHEETS {
+        let title = match i18n::current() {
+            Locale::Zh => zh,
+            Locale::En => en,
+        };
+        let _ = write!(html, "<h2>{}</h2>", escape(title));
+        let range = match excel::read_sheet(&mut workbook, &path_str, Some(sheet)) {
+            Ok((_, range)) => range,
+            Err(e) => {
+                missing(html, &e.to_string());
+                continue;
+            }
+        };
+        let table = Table::from_range(&range);
+        if table.rows.is_empty() {
+            missing(html, &tr!("无数据", "No data"));
+            continue;
+        }
+
+        match *sheet {
+            STATISTICS_SHEET => render_chart(html, &table),
+            MAIN_SHEET => render_column_stats(html, &table),
+            _ => {}
+        }
+        render_table(html, &table);
+    }
+}
+
+// 工作表数据：第一行为表头
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Data>>,
+}
+
+impl Table {
+    pub fn from_range(range: &Range<Data>) -> Table {
+        let mut rows = range.rows();
+        let headers = rows
+            .next()
+            .map(|row| row.iter().map(excel::cell_to_string).collect())
+            .unwrap_or_default();
+        Table { headers, rows: rows.map(|row| row.to_vec()).collect() }
+    }
+
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|header| header == name)
+    }
+}
+
+fn render_table(html: &mut String, table: &Table) {
+    html.push_str("<div class=\"wrap\"><table><tr>");
+    for header in &table.headers {
+        let _ = write!(html, "<th>{}</th>", escape(header));
+    }
+    html.push_str("</tr>");
+    for row in table.rows.iter().take(MAX_TABLE_ROWS) {
+        html.push_str("<tr>");
+        for cell in row {
+            let _ = write!(html, "<td>{}</td>", escape(&format_cell(cell)));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</table></div>");
+    if table.rows.len() > MAX_TABLE_ROWS {
+        let _ = write!(
+            html,
+            "<p class=\"note\">{}</p>",
+            tr!(
+                "仅显示前 {} 行，共 {} 行",
+                "Showing the first {} of {} rows",
+                MAX_TABLE_ROWS,
+                table.rows.len()
+            )
+        );
+    }
+}
+
+// 数值列的基本统计：数量、平均值、标准差、最小值、最大值
+fn render_column_stats(html: &mut String, table: &Table) {
+    let mut stats = Vec::new();
+    for (col, header) in table.headers.iter().enumerate() {
+        let values: Vec<f64> = table
+            .rows
+            .iter()
+            .filter_map(|row| row.get(col))
+            .filter_map(as_number)
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = if values.len() > 1 {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1.0)
+        } else {
+            0.0
+        };
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        stats.push((header, values.len(), mean, variance.sqrt(), min, max));
+    }
+    if stats.is_empty() {
+        return;
+    }
+
+    let _ = write!(
+        html,
+        "<div class=\"wrap\"><table><tr>\
+         <th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+        tr!("列", "Column"),
+        tr!("数量", "Count"),
+        tr!("平均值", "Mean"),
+        tr!("标准差", "Std. dev."),
+        tr!("最小值", "Min"),
+        tr!("最大值", "Max")
+    );
+    for (header, count, mean, std_dev, min, max) in stats {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td>\
+             <td>{:.4}</td><td>{:.4}</td><td>{:.4}</td><td>{:.4}</td></tr>",
+            escape(header),
+            count,
+            mean,
+            std_dev,
+            min,
+            max
+        );
+    }
+    html.push_str("</table></div>");
+}
+
+// 按批次绘制首放容量的横向柱状图（SVG），缺少所需列时跳过
+fn render_chart(html: &mut String, table: &Table) {
+    let Some(label_col) = CHART_LABEL_COLUMNS.iter().find_map(|name| table.column(name)) else {
+        return;
+    };
+    let Some(value_col) = table.column(CHART_VALUE_COLUMN) else { return };
+    let bars: Vec<(String, f64)> = table
+        .rows
+        .iter()
+        .take(MAX_TABLE_ROWS)
+        .filter_map(|row| {
+            let label = row.get(label_col).map(format_cell)?;
+            let value = row.get(value_col).and_then(as_number)?;
+            Some((label, value))
+        })
+        .collect();
+    let max = bars.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+    if bars.is_empty() || max <= 0.0 {
+        return;
+    }
+
+    const LABEL_WIDTH: f64 = 180.0;
+    const BAR_AREA: f64 = 420.0;
+    const ROW_HEIGHT: f64 = 20.0;
+    let height = bars.len() as f64 * ROW_HEIGHT + 10.0;
+    let _ = write!(
+        html,
+        "<p>{}</p>\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-size=\"11\">",
+        tr!("各批次首放容量", "First discharge capacity by batch"),
+        LABEL_WIDTH + BAR_AREA + 80.0,
+        height
+    );
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let y = i as f64 * ROW_HEIGHT + 5.0;
+        let width = (value.max(0.0) / max * BAR_AREA).max(1.0);
+        let _ = write!(
+            html,
+            "<text x=\"{:.0}\" y=\"{:.0}\" text-anchor=\"end\">{}</text>\
+             <rect x=\"{:.0}\" y=\"{:.0}\" width=\"{:.1}\" height=\"{:.0}\" fill=\"#4a90d9\"/>\
+             <text x=\"{:.1}\" y=\"{:.0}\">{:.2}</text>",
+            LABEL_WIDTH - 6.0,
+            y + 13.0,
+            escape(label),
+            LABEL_WIDTH,
+            y,
+            width,
+            ROW_HEIGHT - 4.0,
+            LABEL_WIDTH + width + 4.0,
+            y + 13.0,
+            value
+        );
+    }
+    html.push_str("</svg>");
+}
+
+fn missing(html: &mut String, message: &str) {
+    let _ = write!(html, "<p class=\"missing\">{}</p>", escape(message));
+}
+
+// 查找结果文件夹中的汇总工作簿：优先使用运行清单记录的本次输出（支持 output_name_template
+// 生成的任意文件名），没有清单或清单中没有工作簿时取默认文件名中最新的一个
+pub fn result_workbook(dir: &Path) -> Option<PathBuf> {
+    let recorded = manifest::read(&dir.to_string_lossy()).ok().and_then(|manifest| {
+        manifest
+            .output_files
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| is_xlsx(path) && path.is_file())
+    });
+    recorded.or_else(|| latest_file(dir, RESULT_PREFIX))
+}
+
+fn is_xlsx(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx"))
+}
+
+// 在文件夹中查找指定前缀的最新 .xlsx 文件（按修改时间）
+fn latest_file(dir: &Path, prefix: &str) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            entry.file_name().to_string_lossy().starts_with(prefix) && is_xlsx(&entry.path())
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+pub fn as_number(cell: &Data) -> Option<f64> {
+    match cell {
+        Data::Int(value) => Some(*value as f64),
+        Data::Float(value) if value.is_finite() => Some(*value),
+        _ => None,
+    }
+}
+
+// 浮点数最多保留4位小数，避免报告中出现过长的数字
+fn format_cell(cell: &Data) -> String {
+    match cell {
+        Data::Float(value) if value.fract() != 0.0 => {
+            let formatted = format!("{:.4}", value);
+            formatted.trim_end_matches('0').to_string()
+        }
+        other => excel::cell_to_string(other),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProcessConfig;
+    use crate::manifest::RunManifest;
+    use rust_xlsxwriter::Workbook;
+
+    // 按 main_processor.py 的 _export_results 写入汇总工作簿：主数据、统计数据、异常数据
+    fn write_result_workbook(path: &Path) {
+        let mut workbook = Workbook::new();
+        let sheets: [(&str, &[&str], &[f64]); 3] = [
+            (MAIN_SHEET, &["系列", "主机", "通道", "批次", "首放"], &[1.0, 2.0, 180.5]),
+            (STATISTICS_SHEET, &["系列", "批次", "样品数量", "首放平均值"], &[3.0, 180.5]),
+            (ANOMALY_SHEET, &["系列", "主机", "通道", "批次", "首充"], &[1.0, 3.0, 20.0]),
+        ];
+        for (name, header, values) in sheets {
+            let worksheet = workbook.add_worksheet().set_name(name).unwrap();
+            for (col, title) in header.iter().enumerate() {
+                worksheet.write_string(0, col as u16, *title).unwrap();
+            }
+            // 文本列（系列、批次）在前，数值列在后
+            let text_columns = header.len() - values.len();
+            for col in 0..text_columns {
+                worksheet.write_string(1, col as u16, format!("S{}", col)).unwrap();
+            }
+            for (col, value) in values.iter().enumerate() {
+                worksheet.write_number(1, (text_columns + col) as u16, *value).unwrap();
+            }
+        }
+        workbook.save(path).unwrap();
+    }
+
+    fn report_html(dir: &Path) -> String {
+        let output = dir.join("report.html");
+        let path = generate_report(&dir.to_string_lossy(), Some(&output.to_string_lossy())).unwrap();
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn renders_main_processor_workbook() {
+        let dir = tempfile::tempdir().unwrap();
+        write_result_workbook(&dir.path().join("电池数据汇总表-20240101_120000.xlsx"));
+
+        let html = report_html(dir.path());
+        assert!(!html.contains("class=\"missing\""), "{}", html);
+        assert!(html.contains("电池数据汇总表-20240101_120000.xlsx"));
+        assert!(html.contains("<th>首放平均值</th>"));
+        assert!(html.contains("<svg"));
+    }
+
+    #[test]
+    fn finds_templated_workbook_from_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_folder = dir.path().to_string_lossy().to_string();
+        write_result_workbook(&dir.path().join("A批次_20240101.xlsx"));
+        let run_manifest = RunManifest {
+            created_at: String::new(),
+            app_version: String::new(),
+            python: String::new(),
+            python_version: None,
+            script: String::new(),
+            script_version: None,
+            input_files: Vec::new(),
+            output_files: vec!["A批次_20240101.xlsx".to_string()],
+            config: ProcessConfig::with_defaults(output_folder.clone(), output_folder),
+        };
+        manifest::write(dir.path(), &run_manifest).unwrap();
+
+        assert_eq!(result_workbook(dir.path()), Some(dir.path().join("A批次_20240101.xlsx")));
+    }
+
+    #[test]
+    fn reports_missing_workbook() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("电池数据汇总_20240101_120000.xlsx"), b"").unwrap();
+        assert_eq!(result_workbook(dir.path()), None);
+        assert!(report_html(dir.path()).contains("class=\"missing\""));
+    }
+}
@@ -0,0 +1,90 @@
+// output_to_stdout 模式下 main.py 将主数据表（CSV）逐行输出为 "RESULT_DATA <内容>"，
+// 与日志行共用stdout也能区分；其他模式下脚本不会输出该前缀
+const RESULT_DATA_PREFIX: &str = "RESULT_DATA";
+
+// 结果数据行的内容（前缀后的第一个空格之后的部分，原样保留），其他行返回 None
+pub fn parse_data_line(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix(RESULT_DATA_PREFIX)?;
+    if rest.is_empty() {
+        return Some("");
+    }
+    rest.strip_prefix(' ')
+}
+
+// 将收集到的stdout拆分为日志和结果数据。main.py 对CSV的每一物理行都加前缀（含引号字段内换行后的续行），
+// 拼接后按CSV解析并重新写出，引号字段中的换行保留在同一条记录内；解析失败时原样返回
+pub fn split_data(stdout: &str) -> (String, String) {
+    let mut logs = String::new();
+    let mut data = String::new();
+    for line in stdout.lines() {
+        let (target, content) = match parse_data_line(line) {
+            Some(content) => (&mut data, content),
+            None => (&mut logs, line),
+        };
+        target.push_str(content);
+        target.push('\n');
+    }
+    let data = normalize_csv(&data).unwrap_or(data);
+    (logs, data)
+}
+
+// 按CSV记录（而不是按行）读取并重新写出，每条记录以换行结束
+fn normalize_csv(data: &str) -> Result<String, csv::Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(data.as_bytes());
+    let mut writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(Vec::new());
+    for record in reader.records() {
+        writer.write_record(&record?)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_data_lines() {
+        assert_eq!(parse_data_line("RESULT_DATA 系列,批次"), Some("系列,批次"));
+        assert_eq!(parse_data_line("RESULT_DATA"), Some(""));
+        // 前缀后只去掉一个空格，其余内容原样保留
+        assert_eq!(parse_data_line("RESULT_DATA  ,x"), Some(" ,x"));
+    }
+
+    #[test]
+    fn ignores_other_lines() {
+        assert_eq!(parse_data_line("RESULT_DATAX a"), None);
+        assert_eq!(parse_data_line(" RESULT_DATA a"), None);
+        assert_eq!(parse_data_line("开始数据处理流程"), None);
+    }
+
+    #[test]
+    fn splits_logs_and_data() {
+        let stdout = "日志系统已启动\r\nRESULT_DATA 系列,首放\r\nRESULT_DATA Q3,180.5\r\n处理完成\n";
+        let (logs, data) = split_data(stdout);
+        assert_eq!(logs, "日志系统已启动\n处理完成\n");
+        assert_eq!(data, "系列,首放\nQ3,180.5\n");
+    }
+
+    #[test]
+    fn keeps_quoted_newlines_in_one_record() {
+        let stdout = "RESULT_DATA 系列,说明\nRESULT_DATA Q3,\"第一行\nRESULT_DATA 第二行\"\n处理完成\n";
+        let (logs, data) = split_data(stdout);
+        assert_eq!(logs, "处理完成\n");
+        let mut reader = csv::ReaderBuilder::new().from_reader(data.as_bytes());
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(&records[0][1], "第一行\n第二行");
+    }
+
+    #[test]
+    fn empty_stdout_has_no_data() {
+        assert_eq!(split_data(""), (String::new(), String::new()));
+    }
+}
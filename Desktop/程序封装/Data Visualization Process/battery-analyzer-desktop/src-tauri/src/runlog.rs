@@ -0,0 +1,134 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::Local;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ProcessConfig;
+use crate::error::AppError;
+
+// 日志文件超过该大小时轮转
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+// 保留的历史日志文件数量（runs.log.1 ~ runs.log.3）
+const MAX_ROTATED: usize = 3;
+// 记录的stderr末尾行数
+const STDERR_TAIL_LINES: usize = 20;
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+// 一次运行的记录，由 runner 在解析路径和结束时逐步填写
+pub struct RunEntry {
+    started: String,
+    config: String,
+    pub output_folder: Option<String>,
+    pub python: Option<String>,
+    pub script: Option<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    // 成功时为 None，失败时为错误类型和信息
+    pub error: Option<String>,
+    pub stderr: String,
+    // 取消或超时后清理掉的部分输出文件和新建的目录
+    pub removed_files: Vec<String>,
+}
+
+impl RunEntry {
+    pub fn new(config: &ProcessConfig) -> Self {
+        RunEntry {
+            started: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            config: serde_json::to_string(config).unwrap_or_default(),
+            output_folder: None,
+            python: None,
+            script: None,
+            exit_code: None,
+            duration_ms: 0,
+            error: None,
+            stderr: String::new(),
+            removed_files: Vec::new(),
+        }
+    }
+
+    fn render(&self) -> String {
+        let unknown = || "-".to_string();
+        let mut text = format!(
+            "===== {} {}\n配置: {}\n输出文件夹: {}\nPython: {}\n脚本: {}\n退出码: {}  耗时: {} ms\n",
+            self.started,
+            if self.error.is_none() { "成功" } else { "失败" },
+            self.config,
+            self.output_folder.clone().unwrap_or_else(unknown),
+            self.python.clone().unwrap_or_else(unknown),
+            self.script.clone().unwrap_or_else(unknown),
+            self.exit_code.map(|code| code.to_string()).unwrap_or_else(unknown),
+            self.duration_ms,
+        );
+        if let Some(error) = &self.error {
+            text.push_str(&format!("错误: {}\n", error));
+        }
+        if !self.removed_files.is_empty() {
+            text.push_str(&format!("已删除部分输出（{} 项）:\n", self.removed_files.len()));
+            for file in &self.removed_files {
+                text.push_str("  ");
+                text.push_str(file);
+                text.push('\n');
+            }
+        }
+        let lines: Vec<&str> = self.stderr.lines().collect();
+        if !lines.is_empty() {
+            text.push_str(&format!("stderr（最后 {} 行）:\n", STDERR_TAIL_LINES));
+            for line in &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..] {
+                text.push_str("  ");
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+// 运行日志保存在应用日志目录下的 runs.log 中
+pub fn log_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| AppError::AppDirUnavailable(e.to_string()))?;
+    Ok(dir.join("runs.log"))
+}
+
+// 追加一条运行记录；写日志失败不影响处理结果
+pub fn append(app: &AppHandle, entry: &RunEntry) {
+    if let Err(e) = try_append(app, entry) {
+        log::warn!("写入运行日志失败: {}", e);
+    }
+}
+
+fn try_append(app: &AppHandle, entry: &RunEntry) -> Result<(), AppError> {
+    let _guard = LOCK.lock().unwrap();
+    let path = log_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| AppError::io("创建日志目录失败", e))?;
+    }
+    rotate(&path);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| AppError::io("打开运行日志失败", e))?;
+    file.write_all(entry.render().as_bytes())
+        .map_err(|e| AppError::io("写入运行日志失败", e))
+}
+
+// 超过大小上限时依次重命名 runs.log -> runs.log.1 -> runs.log.2 ...，最旧的被覆盖
+fn rotate(path: &Path) {
+    let too_large = fs::metadata(path).map(|m| m.len() >= MAX_LOG_BYTES).unwrap_or(false);
+    if !too_large {
+        return;
+    }
+    let rotated = |index: usize| PathBuf::from(format!("{}.{}", path.display(), index));
+    for index in (1..MAX_ROTATED).rev() {
+        let _ = fs::rename(rotated(index), rotated(index + 1));
+    }
+    let _ = fs::rename(path, rotated(1));
+}
@@ -0,0 +1,1097 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::Utc;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::analysis::{AnalysisError, StderrClassifier, StderrLevel};
+use crate::config::{FieldError, ProcessConfig};
+use crate::error::AppError;
+use crate::i18n::tr;
+use crate::joboutput::{JobOutput, JobOutputStore};
+use crate::manifest::{self, RunManifest};
+use crate::output::OutputFile;
+use crate::process::{self, ProcessRegistry, WaitOutcome};
+use crate::runlog::RunEntry;
+use crate::scan::{self, FileInfo, FileKind, ScanOptions};
+use crate::{analysis, lastrun, output, progress, python, resultdata, runlog, script, tempfiles};
+
+// 实时日志事件名（stdout）
+const LOG_EVENT: &str = "battery://log";
+// stderr输出事件名，每行附带级别
+const ERROR_EVENT: &str = "battery://error";
+// 进度事件名
+const PROGRESS_EVENT: &str = "battery://progress";
+// 重试事件名
+const RETRY_EVENT: &str = "battery://retry";
+// 运行结束（含重试后最终失败）事件名，携带完整结果
+const JOB_DONE_EVENT: &str = "battery://job-done";
+// 未以换行结束的输出等待该时间后先行发送
+const PARTIAL_LINE_TIMEOUT: Duration = Duration::from_millis(500);
+// 重试等待时间最多加倍的次数
+const MAX_BACKOFF_DOUBLINGS: u32 = 6;
+// 未指定 output_folder 时结果写入的输入文件夹子目录
+const DEFAULT_OUTPUT_DIR: &str = "output";
+
+// 数据处理结果
+#[derive(Debug, Serialize)]
+pub struct ProcessResult {
+    // 未能启动Python时为 None
+    job_id: Option<u64>,
+    input_folder: String,
+    success: bool,
+    exit_code: Option<i32>,
+    // 被信号终止时的信号编号（仅Unix）
+    signal: Option<i32>,
+    stdout: String,
+    stderr: String,
+    // Python脚本报告的结构化错误（ANALYSIS_ERROR 行），没有时显示原始 stderr
+    analysis_errors: Vec<AnalysisError>,
+    // 实际使用的输出文件夹（绝对路径，见 resolve_output_folder）
+    output_folder: String,
+    // 本次运行在输出文件夹中新建或更新的文件
+    output_files: Vec<OutputFile>,
+    // output_to_stdout 模式下脚本通过stdout返回的结果（RESULT_DATA 行的内容），该模式下 stdout 只含日志
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_data: Option<String>,
+    duration_ms: u64,
+    // 实际运行次数（含重试）
+    attempts: u32,
+    // 执行的完整命令行（解释器、脚本和参数），便于在终端中手动重新运行。
+    // 不包含环境变量（可能含有密钥）；--config 指向的临时配置文件在运行结束后已删除
+    command_line: String,
+    // 运行前检查时发现的非致命问题（如磁盘空间不足）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    // skip_invalid 为 true 时因为空或过小而跳过的输入文件
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped_files: Vec<SkippedFile>,
+    // 运行失败的原因（仅批量处理汇总失败结果时出现）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<AppError>,
+}
+
+impl ProcessResult {
+    pub fn output_files(&self) -> &[OutputFile] {
+        &self.output_files
+    }
+
+    // 由失败的运行构造结果，用于批量处理时汇总
+    pub fn from_error(input_folder: String, error: AppError) -> Self {
+        let (exit_code, signal, stdout, stderr, analysis_errors) = error_streams(&error);
+        ProcessResult {
+            job_id: None,
+            input_folder,
+            success: false,
+            exit_code,
+            signal,
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+            analysis_errors: analysis_errors.to_vec(),
+            output_folder: String::new(),
+            output_files: Vec::new(),
+            output_data: None,
+            duration_ms: 0,
+            attempts: 1,
+            command_line: String::new(),
+            warnings: Vec::new(),
+            skipped_files: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+// 失败的运行中捕获到的退出码、信号、输出和结构化错误
+fn error_streams(error: &AppError) -> (Option<i32>, Option<i32>, &str, &str, &[AnalysisError]) {
+    match error {
+        AppError::ProcessFailed { code, signal, stdout, stderr, errors } => {
+            (*code, *signal, stdout, stderr, errors)
+        }
+        AppError::Timeout { stdout, stderr, .. } => (None, None, stdout, stderr, &[]),
+        _ => (None, None, "", "", &[]),
+    }
+}
+
+// 由运行结果构造保存到 JobOutputStore 的记录
+fn job_output(
+    job_id: u64,
+    input_folder: &str,
+    attempts: u32,
+    result: &Result<ProcessResult, AppError>,
+) -> JobOutput {
+    let mut output = JobOutput {
+        job_id,
+        input_folder: input_folder.to_string(),
+        finished_at: Utc::now().to_rfc3339(),
+        success: false,
+        exit_code: None,
+        signal: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        stdout_truncated: false,
+        stderr_truncated: false,
+        analysis_errors: Vec::new(),
+        output_files: Vec::new(),
+        duration_ms: 0,
+        attempts,
+        error_kind: None,
+        error: None,
+    };
+    match result {
+        Ok(result) => {
+            output.success = result.success;
+            output.exit_code = result.exit_code;
+            output.signal = result.signal;
+            output.stdout = result.stdout.clone();
+            output.stderr = result.stderr.clone();
+            output.analysis_errors = result.analysis_errors.clone();
+            output.output_files = result.output_files.clone();
+            output.duration_ms = result.duration_ms;
+        }
+        Err(e) => {
+            let (exit_code, signal, stdout, stderr, analysis_errors) = error_streams(e);
+            output.exit_code = exit_code;
+            output.signal = signal;
+            output.stdout = stdout.to_string();
+            output.stderr = stderr.to_string();
+            output.analysis_errors = analysis_errors.to_vec();
+            output.error_kind = Some(e.kind());
+            output.error = Some(e.to_string());
+        }
+    }
+    output
+}
+
+// 因为空或过小而跳过的输入文件
+#[derive(Debug, Serialize)]
+struct SkippedFile {
+    path: String,
+    size: u64,
+}
+
+// 试运行计划：将要处理的文件和结果输出位置
+#[derive(Debug, Serialize)]
+pub struct ProcessPlan {
+    input_folder: String,
+    files: Vec<FileInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped_files: Vec<SkippedFile>,
+    // 结果输出位置的绝对路径
+    resolved_output: String,
+    // 结果将直接写入输入文件夹（output_folder 为 "."）
+    output_is_input: bool,
+    estimated_count: usize,
+}
+
+// 一次处理请求的结果：实际运行结果或试运行计划。
+// 每次请求只返回一个，不必为减小体积而装箱
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum ProcessOutcome {
+    Run(ProcessResult),
+    Plan(ProcessPlan),
+}
+
+impl ProcessOutcome {
+    pub fn success(&self) -> bool {
+        match self {
+            ProcessOutcome::Run(result) => result.success,
+            ProcessOutcome::Plan(_) => true,
+        }
+    }
+}
+
+// 实时日志事件负载
+#[derive(Debug, Clone, Serialize)]
+struct LogLine {
+    job_id: u64,
+    stream: &'static str,
+    line: String,
+}
+
+// stderr输出事件负载
+#[derive(Debug, Clone, Serialize)]
+struct ErrorLine {
+    job_id: u64,
+    level: StderrLevel,
+    line: String,
+}
+
+// 在后台线程中逐行读取子进程输出，每行作为事件发送给前端，线程结束时返回完整输出。
+// stdout和stderr各用一个线程读取，避免一个管道写满时阻塞子进程。
+// stdout行发送到 battery://log，其中的进度行作为进度事件发送（附带按 started 估算的剩余时间），
+// 不计入日志；stderr行发送到 battery://error。
+// 未以换行结束的内容超过 PARTIAL_LINE_TIMEOUT 没有后续时先行发送，进程退出时发送剩余内容。
+// app 为 None（headless 模式）时只收集输出，不发送事件
+fn spawn_line_reader<R: Read + Send + 'static>(
+    app: Option<AppHandle>,
+    job_id: u64,
+    mut reader: R,
+    stream: &'static str,
+    started: Instant,
+    split_cr: bool,
+) -> JoinHandle<String> {
+    thread::spawn(move || {
+        // 阻塞读取放在单独的线程，本线程按超时检查未完成的行
+        let (tx, rx) = mpsc::channel::<Vec<u8>>();
+        let raw_reader = thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if tx.send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+        });
+
+        let mut sink = LineSink {
+            app,
+            job_id,
+            stream,
+            started,
+            classifier: StderrClassifier::default(),
+            collected: String::new(),
+            continued: false,
+        };
+        // 按字节分行，避免非UTF-8输出（如GBK）导致读取中断
+        let mut pending: Vec<u8> = Vec::new();
+        let mut after_cr = false;
+        loop {
+            match rx.recv_timeout(PARTIAL_LINE_TIMEOUT) {
+                Ok(chunk) => {
+                    for byte in chunk {
+                        match byte {
+                            // \r\n 只结束一行
+                            b'\n' if after_cr && pending.is_empty() && !sink.continued => {}
+                            b'\n' => sink.line(&pending, true),
+                            b'\r' if split_cr => sink.line(&pending, true),
+                            _ => {
+                                pending.push(byte);
+                                after_cr = false;
+                                continue;
+                            }
+                        }
+                        after_cr = byte == b'\r';
+                        pending.clear();
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) if !pending.is_empty() => {
+                    sink.line(&pending, false);
+                    pending.clear();
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        if !pending.is_empty() {
+            sink.line(&pending, true);
+        }
+        let _ = raw_reader.join();
+        sink.collected
+    })
+}
+
+// 按行分发子进程输出并收集完整文本
+struct LineSink {
+    app: Option<AppHandle>,
+    job_id: u64,
+    stream: &'static str,
+    started: Instant,
+    classifier: StderrClassifier,
+    collected: String,
+    // 上一段是因超时先行发送的未完成行，本段是它的后续
+    continued: bool,
+}
+
+impl LineSink {
+    // complete 为 false 表示行尚未结束（超时先行发送），收集的文本中不追加换行
+    fn line(&mut self, bytes: &[u8], complete: bool) {
+        let text = String::from_utf8_lossy(bytes);
+        let line = text.trim_end_matches('\r');
+        let (job_id, stream) = (self.job_id, self.stream);
+        if !(self.continued && line.is_empty()) {
+            if stream == "stdout" {
+                // 进度行不计入日志
+                if let Some(progress) = progress::parse_progress(job_id, line) {
+                    if let Some(app) = &self.app {
+                        let _ = app.emit(PROGRESS_EVENT, progress.with_eta(self.started.elapsed()));
+                    }
+                    self.continued = !complete;
+                    return;
+                }
+                // 结果数据行不作为日志发送，收集后在运行结束时拆分
+                let is_data = resultdata::parse_data_line(line).is_some();
+                if let Some(app) = self.app.as_ref().filter(|_| !is_data) {
+                    let _ = app.emit(LOG_EVENT, LogLine { job_id, stream, line: line.to_string() });
+                }
+            } else if let Some(app) = &self.app {
+                let level = self.classifier.classify(line);
+                let line = line.to_string();
+                let _ = app.emit(ERROR_EVENT, ErrorLine { job_id, level, line });
+            }
+        }
+        self.collected.push_str(line);
+        if complete {
+            self.collected.push('\n');
+        }
+        self.continued = !complete;
+    }
+}
+
+// 执行处理请求：dry_run 时只返回计划，不启动Python
+pub fn execute(app: &AppHandle, config: ProcessConfig) -> Result<ProcessOutcome, AppError> {
+    execute_with_id(app, config, None)
+}
+
+// 同 execute，reserved_id 为任务队列预留的任务ID，子进程以该ID登记
+pub fn execute_with_id(
+    app: &AppHandle,
+    config: ProcessConfig,
+    reserved_id: Option<u64>,
+) -> Result<ProcessOutcome, AppError> {
+    if config.dry_run {
+        plan(&config).map(ProcessOutcome::Plan)
+    } else {
+        run_with_retry(app, config, reserved_id).map(ProcessOutcome::Run)
+    }
+}
+
+// 重试事件负载
+#[derive(Debug, Clone, Serialize)]
+struct RetryEvent<'a> {
+    job_id: Option<u64>,
+    input_folder: &'a str,
+    // 即将开始的重试是第几次
+    attempt: u32,
+    max_retries: u32,
+    delay_ms: u64,
+    reason: String,
+}
+
+// 运行结束事件负载：成功时 result 为完整结果，失败时 error 为错误信息（结构与命令返回的错误相同）。
+// 未预留任务ID且未能启动Python时 job_id 为 None
+#[derive(Debug, Clone, Serialize)]
+struct JobDoneEvent<'a> {
+    job_id: Option<u64>,
+    input_folder: &'a str,
+    success: bool,
+    result: Option<&'a ProcessResult>,
+    error: Option<&'a AppError>,
+}
+
+// 运行失败且错误可重试时，按指数退避重新运行，最多 max_retries 次。
+// 结束时保存输出供 get_job_output 查看，并发送 battery://job-done 事件，前端离开发起页面后也能得知结果
+fn run_with_retry(
+    app: &AppHandle,
+    config: ProcessConfig,
+    reserved_id: Option<u64>,
+) -> Result<ProcessResult, AppError> {
+    let registry = app.state::<ProcessRegistry>();
+    // 需要重试时预留任务ID，各次运行共用该ID，取消命令在两次运行之间也能生效
+    let owns_reservation = reserved_id.is_none() && config.max_retries > 0;
+    let job_id = if owns_reservation { Some(registry.reserve()) } else { reserved_id };
+
+    let started_at = SystemTime::now();
+    let mut attempt = 1;
+    let result = loop {
+        match run_logged(app, config.clone(), job_id) {
+            Err(e) if attempt <= config.max_retries && e.is_retryable() => {
+                let delay_ms = config
+                    .retry_delay_ms
+                    .saturating_mul(1 << (attempt - 1).min(MAX_BACKOFF_DOUBLINGS));
+                if !config.headless {
+                    let _ = app.emit(
+                        RETRY_EVENT,
+                        RetryEvent {
+                            job_id,
+                            input_folder: &config.input_folder,
+                            attempt,
+                            max_retries: config.max_retries,
+                            delay_ms,
+                            reason: e.to_string(),
+                        },
+                    );
+                }
+                thread::sleep(Duration::from_millis(delay_ms));
+                if job_id.is_some_and(|id| !registry.is_reserved(id)) {
+                    break Err(AppError::Cancelled);
+                }
+                attempt += 1;
+            }
+            other => break other,
+        }
+    };
+
+    if let (true, Some(id)) = (owns_reservation, job_id) {
+        registry.release(id);
+    }
+    let result = result.map(|mut result| {
+        result.attempts = attempt;
+        result
+    });
+    // 记录本次运行的开始时间，供 read_directory_since 只列出之后新增或修改的文件
+    if result.as_ref().is_ok_and(|result| result.success) {
+        if let Ok(elapsed) = started_at.duration_since(UNIX_EPOCH) {
+            lastrun::record_quietly(app, &config.input_folder, elapsed.as_secs());
+        }
+    }
+    let done_id = result.as_ref().ok().and_then(|result| result.job_id).or(job_id);
+    if let Some(id) = done_id {
+        app.state::<JobOutputStore>()
+            .record(job_output(id, &config.input_folder, attempt, &result));
+    }
+    if !config.headless {
+        let _ = app.emit(
+            JOB_DONE_EVENT,
+            JobDoneEvent {
+                job_id: done_id,
+                input_folder: &config.input_folder,
+                success: result.as_ref().is_ok_and(|result| result.success),
+                result: result.as_ref().ok(),
+                error: result.as_ref().err(),
+            },
+        );
+    }
+    result
+}
+
+// 实际运行并写入运行日志，便于事后排查
+fn run_logged(
+    app: &AppHandle,
+    config: ProcessConfig,
+    reserved_id: Option<u64>,
+) -> Result<ProcessResult, AppError> {
+    let mut entry = RunEntry::new(&config);
+    let started = Instant::now();
+    let result = run(app, config, &mut entry, reserved_id);
+    entry.duration_ms = started.elapsed().as_millis() as u64;
+    match &result {
+        Ok(result) => {
+            entry.exit_code = result.exit_code;
+            entry.stderr = result.stderr.clone();
+        }
+        Err(e) => {
+            match e {
+                AppError::ProcessFailed { code, stderr, .. } => {
+                    entry.exit_code = *code;
+                    entry.stderr = stderr.clone();
+                }
+                AppError::Timeout { stderr, .. } => entry.stderr = stderr.clone(),
+                _ => {}
+            }
+            entry.error = Some(format!("{}: {}", e.kind(), e));
+        }
+    }
+    runlog::append(app, &entry);
+    result
+}
+
+// 单个文件的处理结果
+#[derive(Debug, Serialize)]
+pub struct FileOutcome {
+    success: bool,
+    // Python脚本针对该文件报告的结构化错误
+    errors: Vec<AnalysisError>,
+}
+
+// 指定文件处理结果：整体运行结果及按文件路径索引的单文件结果
+#[derive(Debug, Serialize)]
+pub struct FilesOutcome {
+    result: ProcessResult,
+    files: BTreeMap<String, FileOutcome>,
+}
+
+// 只处理指定的数据文件，文件列表通过配置传给Python。
+// Python运行失败时结果中 success 为 false，启动前的检查失败仍返回错误
+pub fn process_files(
+    app: &AppHandle,
+    mut config: ProcessConfig,
+    paths: Vec<String>,
+) -> Result<FilesOutcome, AppError> {
+    if paths.is_empty() {
+        return Err(AppError::InvalidConfig(vec![FieldError {
+            field: "files",
+            message: tr!("必须指定至少一个文件", "At least one file must be specified"),
+        }]));
+    }
+    if let Some(missing) = paths.iter().find(|path| !Path::new(path).is_file()) {
+        return Err(AppError::FileNotFound(missing.clone()));
+    }
+    // 分析脚本只读取 .xlsx 文件（见 main_processor.py 的 _input_files），其他类型不交给Python，
+    // 在单文件结果中注明
+    let (paths, unsupported): (Vec<String>, Vec<String>) = paths
+        .into_iter()
+        .partition(|path| FileKind::from_path(Path::new(path)) == Some(FileKind::Xlsx));
+    if paths.is_empty() {
+        return Err(AppError::InvalidConfig(vec![FieldError {
+            field: "files",
+            message: tr!(
+                "分析脚本只能处理 .xlsx 文件: {}",
+                "The analysis script can only process .xlsx files: {}",
+                unsupported.join(", ")
+            ),
+        }]));
+    }
+    // 未指定输入文件夹时使用第一个文件所在目录，作为默认输出位置
+    if config.input_folder.trim().is_empty() {
+        if let Some(parent) = Path::new(&paths[0]).parent() {
+            config.input_folder = parent.to_string_lossy().to_string();
+        }
+    }
+    config.files = paths.clone();
+    config.dry_run = false;
+
+    let input_folder = config.input_folder.clone();
+    let result = match run_with_retry(app, config, None) {
+        Ok(result) => result,
+        Err(e @ (AppError::ProcessFailed { .. } | AppError::Timeout { .. } | AppError::Cancelled)) => {
+            ProcessResult::from_error(input_folder, e)
+        }
+        Err(e) => return Err(e),
+    };
+
+    let files = paths
+        .into_iter()
+        .map(|path| {
+            let name = Path::new(&path).file_name().map(|n| n.to_string_lossy().to_string());
+            let errors: Vec<AnalysisError> = result
+                .analysis_errors
+                .iter()
+                .filter(|error| {
+                    error.file.as_deref().is_some_and(|file| file == path || Some(file) == name.as_deref())
+                })
+                .cloned()
+                .collect();
+            let outcome = FileOutcome {
+                success: result.success && errors.is_empty(),
+                errors,
+            };
+            (path, outcome)
+        })
+        .chain(unsupported.into_iter().map(|path| {
+            let error = AnalysisError {
+                file: Some(path.clone()),
+                row: None,
+                message: tr!(
+                    "不支持的文件类型，分析脚本只能处理 .xlsx 文件",
+                    "Unsupported file type, the analysis script can only process .xlsx files"
+                ),
+            };
+            (path, FileOutcome { success: false, errors: vec![error] })
+        }))
+        .collect();
+    Ok(FilesOutcome { result, files })
+}
+
+// 启动前检查：校验配置、确认输入文件夹存在，返回实际使用的输出文件夹
+fn preflight(config: &ProcessConfig) -> Result<String, AppError> {
+    // 配置无效时不启动Python
+    let errors = config.validate();
+    if !errors.is_empty() {
+        return Err(AppError::InvalidConfig(errors));
+    }
+
+    // 检查输入文件夹是否存在
+    if !Path::new(&config.input_folder).exists() {
+        return Err(AppError::InputFolderMissing(config.input_folder.clone()));
+    }
+
+    // 被占用的文件可能只读到部分内容，导致结果不完整
+    if config.fail_on_locked {
+        let locked = locked_inputs(config)?;
+        if !locked.is_empty() {
+            return Err(AppError::FilesLocked(locked));
+        }
+    }
+
+    // 结果通过stdout返回时不使用输出文件夹
+    if config.output_to_stdout {
+        return Ok(String::new());
+    }
+
+    // 按覆盖策略确定最终位置
+    let output_folder = resolve_output_folder(config);
+    output::apply_overwrite_policy(
+        config.overwrite_policy,
+        Path::new(&config.input_folder),
+        &output_folder.to_string_lossy(),
+    )
+}
+
+// 输出文件夹：为空时为输入文件夹下的 output 子目录，相对路径（如 ./results）相对于输入文件夹，
+// 避免结果与原始数据混在一起
+fn resolve_output_folder(config: &ProcessConfig) -> PathBuf {
+    let output = Path::new(&config.output_folder);
+    if output.is_absolute() {
+        return output.to_path_buf();
+    }
+    let relative = if config.output_folder.trim().is_empty() {
+        Path::new(DEFAULT_OUTPUT_DIR)
+    } else {
+        output
+    };
+    // 去掉 "." 段，./results 与 results 得到相同的路径
+    relative
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .fold(PathBuf::from(&config.input_folder), |path, component| path.join(component))
+}
+
+// 列出将要处理的输入文件中正被占用的文件：指定了 files 时只检查这些文件
+fn locked_inputs(config: &ProcessConfig) -> Result<Vec<String>, AppError> {
+    if !config.files.is_empty() {
+        return Ok(listed_files(config)?
+            .into_iter()
+            .filter(|file| scan::is_file_locked(Path::new(file)))
+            .cloned()
+            .collect());
+    }
+    // 目录列表默认不检测占用，这里只对将要处理的Excel文件检测
+    let files = folder_inputs(config)?;
+    Ok(files
+        .into_iter()
+        .filter(|file| file.is_excel && scan::is_file_locked(Path::new(&file.path)))
+        .map(|file| file.path)
+        .collect())
+}
+
+// 指定的 files 中文件名与 pattern 匹配的文件，未设置 pattern 时为全部
+fn listed_files(config: &ProcessConfig) -> Result<Vec<&String>, AppError> {
+    let pattern = scan::file_pattern(config.pattern.as_deref())?;
+    Ok(config
+        .files
+        .iter()
+        .filter(|file| {
+            pattern.as_ref().map_or(true, |pattern| scan::matches_pattern(pattern, Path::new(file)))
+        })
+        .collect())
+}
+
+// 输入文件夹中的数据文件，设置了 pattern 时只保留文件名匹配的文件
+fn folder_inputs(config: &ProcessConfig) -> Result<Vec<FileInfo>, AppError> {
+    let files = scan::scan_directory(Path::new(&config.input_folder), &ScanOptions::default())?;
+    Ok(match scan::file_pattern(config.pattern.as_deref())? {
+        Some(pattern) => scan::filter_by_pattern(files, &pattern),
+        None => files,
+    })
+}
+
+// 将要处理的输入文件：指定了 files 时为这些文件（无法读取的文件交给Python报错），否则为输入文件夹中的数据文件
+pub fn input_files(config: &ProcessConfig) -> Result<Vec<(String, u64, FileKind)>, AppError> {
+    if !config.files.is_empty() {
+        return Ok(listed_files(config)?
+            .into_iter()
+            .filter_map(|file| {
+                let kind = FileKind::from_path(Path::new(file))?;
+                let size = fs::metadata(file).ok()?.len();
+                Some((file.clone(), size, kind))
+            })
+            .collect());
+    }
+    let files = folder_inputs(config)?;
+    Ok(files.into_iter().map(|file| (file.path, file.size, file.kind)).collect())
+}
+
+// 设置了 pattern 时将匹配的文件写入 config.files，使Python只处理这些文件；没有匹配的文件时返回错误
+fn apply_pattern(config: &mut ProcessConfig) -> Result<(), AppError> {
+    let Some(pattern) = config.pattern.clone().filter(|pattern| !pattern.trim().is_empty()) else {
+        return Ok(());
+    };
+    let files: Vec<String> = input_files(config)?.into_iter().map(|(path, ..)| path).collect();
+    if files.is_empty() {
+        return Err(AppError::NoMatchingFiles(pattern));
+    }
+    config.files = files;
+    Ok(())
+}
+
+// 检查空文件或过小的输入文件：skip_invalid 为 true 时将其余文件写入 config.files 并返回被跳过的文件，
+// 否则（或没有剩余文件时）返回错误
+fn skip_invalid_inputs(config: &mut ProcessConfig) -> Result<Vec<SkippedFile>, AppError> {
+    let (invalid, valid): (Vec<_>, Vec<_>) = input_files(config)?
+        .into_iter()
+        .partition(|(_, size, kind)| *size < kind.min_valid_size());
+    if invalid.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !config.skip_invalid || valid.is_empty() {
+        return Err(AppError::InvalidInputFiles(invalid.into_iter().map(|(path, ..)| path).collect()));
+    }
+    config.files = valid.into_iter().map(|(path, ..)| path).collect();
+    Ok(invalid.into_iter().map(|(path, size, _)| SkippedFile { path, size }).collect())
+}
+
+// 试运行：列出将要处理的文件和输出位置，不创建目录也不启动Python
+fn plan(config: &ProcessConfig) -> Result<ProcessPlan, AppError> {
+    let resolved_output = preflight(config)?;
+    let mut selected = config.clone();
+    apply_pattern(&mut selected)?;
+    let skipped_files = skip_invalid_inputs(&mut selected)?;
+    let mut files = folder_inputs(config)?;
+    files.retain(|file| !skipped_files.iter().any(|skipped| skipped.path == file.path));
+
+    Ok(ProcessPlan {
+        input_folder: config.input_folder.clone(),
+        estimated_count: files.len(),
+        files,
+        skipped_files,
+        output_is_input: !config.output_to_stdout
+            && absolute_path(&resolve_output_folder(config))
+                == absolute_path(Path::new(&config.input_folder)),
+        // output_to_stdout 模式下为空
+        resolved_output: if resolved_output.is_empty() {
+            resolved_output
+        } else {
+            absolute_string(&resolved_output)
+        },
+    })
+}
+
+// 同步执行一次完整的分析流程：预检查、启动Python、等待结束并汇总结果
+fn run(
+    app: &AppHandle,
+    mut config: ProcessConfig,
+    entry: &mut RunEntry,
+    reserved_id: Option<u64>,
+) -> Result<ProcessResult, AppError> {
+    let registry = app.state::<ProcessRegistry>();
+    // headless 模式下关闭脚本的进度和详细输出，只返回最终结果
+    if config.headless {
+        config.verbose = false;
+        config.enable_progress_bar = false;
+    }
+
+    // 创建输出文件夹（如果不存在）；Python的工作目录与本进程不同，传递的路径一律转为绝对路径。
+    // output_to_stdout 模式下不使用输出文件夹，output_folder 为空
+    let to_disk = !config.output_to_stdout;
+    let output_folder = preflight(&config)?;
+    let output_folder = if to_disk { absolute_string(&output_folder) } else { output_folder };
+    apply_pattern(&mut config)?;
+    let skipped_files = skip_invalid_inputs(&mut config)?;
+    config.input_folder = absolute_string(&config.input_folder);
+    config.files = config.files.iter().map(|file| absolute_string(file)).collect();
+    let mut warnings = Vec::new();
+    // 记录创建前已存在的目录，取消后只删除本次运行新建的目录
+    let dirs_before = if to_disk { output::snapshot_dirs(Path::new(&output_folder)) } else { Default::default() };
+    if to_disk {
+        entry.output_folder = Some(output_folder.clone());
+        fs::create_dir_all(&output_folder).map_err(|e| AppError::io("创建输出文件夹失败", e))?;
+
+        // 目录创建成功不代表可写（如只读网络共享），启动前先做写入探测
+        output::ensure_writable(Path::new(&output_folder))?;
+        // 结果大小按输入文件总大小估计，剩余空间不足时不启动，避免写到一半磁盘写满留下不完整的结果
+        if config.disk_space_factor > 0.0 {
+            let input_bytes = input_files(&config)?.iter().map(|(_, size, _)| size).sum();
+            output::check_disk_space(Path::new(&output_folder), config.required_space(input_bytes))?;
+        }
+        warnings.extend(output::low_space_warning(Path::new(&output_folder)));
+    }
+    if !skipped_files.is_empty() {
+        warnings.push(tr!(
+            "已跳过 {} 个空文件或过小的文件",
+            "Skipped {} empty or too small files",
+            skipped_files.len()
+        ));
+    }
+
+    // 定位Python脚本（找不到时错误中包含尝试过的路径）
+    let python_script = absolute_path(&script::resolve_script(app, config.script_path.as_deref())?);
+    entry.script = Some(python_script.to_string_lossy().to_string());
+
+    // 解析Python解释器（含虚拟环境/conda环境），启动前给出明确的错误信息
+    let python = python::resolve_runtime(
+        config.python_executable.as_deref(),
+        config.venv_path.as_deref(),
+        config.conda_env.as_deref(),
+    )?;
+    entry.python = Some(python.executable.to_string_lossy().to_string());
+
+    // 构建命令参数：默认通过临时JSON文件传递配置，临时文件在运行结束后自动删除
+    let mut cmd = python.command();
+    cmd.arg(&python_script);
+    let working_dir = match config.working_dir.as_deref().filter(|dir| !dir.is_empty()) {
+        Some(dir) if !Path::new(dir).is_dir() => return Err(AppError::DirectoryNotFound(dir.to_string())),
+        Some(dir) => PathBuf::from(dir),
+        None => python_script.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    if !working_dir.as_os_str().is_empty() {
+        cmd.current_dir(&working_dir);
+    }
+    // 关闭Python输出缓冲，使日志和进度实时到达；用户配置的环境变量在其后设置
+    cmd.env("PYTHONUNBUFFERED", "1");
+    if let Some(env) = &config.env {
+        cmd.envs(env);
+    }
+    let _config_file = if config.legacy_args {
+        append_legacy_args(&mut cmd, &config, &output_folder);
+        None
+    } else {
+        // 临时文件写入专用目录，启动时顺便清理异常退出遗留的文件
+        let file = write_config_file(&config, &output_folder, &tempfiles::prepare(app)?)?;
+        cmd.arg("--config").arg(file.path());
+        Some(file)
+    };
+    // 输出控制参数在两种模式下都通过命令行传递
+    append_output_args(&mut cmd, &config);
+    // 用户的额外参数放在最后
+    cmd.args(&config.extra_args);
+
+    let command_line = command_line(&cmd);
+
+    // 记录运行前的输出文件，用于识别新生成的结果
+    let snapshot = || {
+        if to_disk {
+            output::snapshot_files(Path::new(&output_folder))
+        } else {
+            Default::default()
+        }
+    };
+    let files_before = snapshot();
+
+    // 启动Python脚本，stdout/stderr通过管道实时读取
+    process::isolate_process_group(&mut cmd);
+    let started = Instant::now();
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| spawn_error(&python.executable, e))?;
+
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+
+    // 登记子进程，使 cancel_processing 可以终止它
+    let job_id = match reserved_id {
+        Some(job_id) => {
+            registry.register_reserved(job_id, child);
+            job_id
+        }
+        None => registry.register(child),
+    };
+
+    let events = (!config.headless).then(|| app.clone());
+    let stdout_reader =
+        stdout_pipe.map(|out| {
+        spawn_line_reader(events.clone(), job_id, out, "stdout", started, config.line_buffer)
+    });
+    let stderr_reader =
+        stderr_pipe.map(|err| {
+        spawn_line_reader(events.clone(), job_id, err, "stderr", started, config.line_buffer)
+    });
+
+    let outcome = registry.wait(job_id, config.timeout_secs.map(Duration::from_secs));
+
+    // 等待读取线程结束，确保不会遗留线程
+    let stdout = stdout_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    let stderr = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    let (stdout, output_data) = match resultdata::split_data(&stdout) {
+        (logs, data) if config.output_to_stdout => (logs, Some(data)),
+        _ => (stdout, None),
+    };
+
+    // 被取消或超时时按需删除本次运行新建的文件，恢复运行前的状态
+    if config.cleanup_on_abort
+        && to_disk
+        && matches!(outcome, Ok(WaitOutcome::Cancelled | WaitOutcome::TimedOut(_)))
+    {
+        entry.removed_files =
+            output::remove_created_files(&files_before, &dirs_before, Path::new(&output_folder));
+    }
+
+    let status = match outcome {
+        Ok(WaitOutcome::Exited(status)) => status,
+        Ok(WaitOutcome::Cancelled) => return Err(AppError::Cancelled),
+        Ok(WaitOutcome::TimedOut(elapsed)) => {
+            return Err(AppError::Timeout {
+                elapsed_secs: elapsed.as_secs(),
+                stdout,
+                stderr,
+            })
+        }
+        Err(e) => return Err(AppError::io("等待Python脚本结束失败", e)),
+    };
+
+    let analysis_errors = analysis::parse_errors(&stderr);
+    if !status.success() {
+        return Err(AppError::ProcessFailed {
+            code: status.code(),
+            signal: process::exit_signal(&status),
+            stdout,
+            stderr,
+            errors: analysis_errors,
+        });
+    }
+
+    let output_files = output::changed_files(&files_before, &snapshot());
+
+    // 在输出文件夹中记录本次运行的配置、版本和输出文件，写入失败不影响结果
+    if to_disk {
+        let run_manifest =
+            RunManifest::new(app, &config, &output_folder, &output_files, &python, &python_script);
+        if let Err(e) = manifest::write(Path::new(&output_folder), &run_manifest) {
+            warnings.push(e.to_string());
+        }
+    }
+
+    Ok(ProcessResult {
+        job_id: Some(job_id),
+        input_folder: config.input_folder,
+        success: true,
+        exit_code: status.code(),
+        signal: process::exit_signal(&status),
+        stdout,
+        stderr,
+        analysis_errors,
+        output_files,
+        output_data,
+        output_folder,
+        duration_ms: started.elapsed().as_millis() as u64,
+        attempts: 1,
+        command_line,
+        warnings,
+        skipped_files,
+        error: None,
+    })
+}
+
+// 启动失败时区分解释器不存在（解析后被删除、或PATH中的名称无法启动）、不可执行与其他原因，
+// 使前端可以提示安装Python，而不是显示数据处理错误
+fn spawn_error(executable: &Path, e: io::Error) -> AppError {
+    let executable = executable.to_string_lossy().to_string();
+    match e.kind() {
+        io::ErrorKind::NotFound => AppError::PythonNotFound { tried: vec![executable] },
+        io::ErrorKind::PermissionDenied => AppError::PythonNotExecutable(executable),
+        _ => AppError::SpawnFailed(e),
+    }
+}
+
+// 按当前平台shell的规则拼接命令行，含空格或特殊字符的参数加引号
+fn command_line(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| shell_quote(&arg.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(windows)]
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"', '&', '|', '<', '>', '^']) {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('"', "\\\""))
+}
+
+#[cfg(not(windows))]
+fn shell_quote(arg: &str) -> String {
+    let safe = |c: char| c.is_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+// 相对路径按本进程的当前目录转换为绝对路径
+fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    std::env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn absolute_string(path: &str) -> String {
+    absolute_path(Path::new(path)).to_string_lossy().to_string()
+}
+
+// 将配置写入临时JSON文件，output_folder 替换为实际使用的输出目录
+fn write_config_file(
+    config: &ProcessConfig,
+    output_folder: &str,
+    dir: &Path,
+) -> Result<NamedTempFile, AppError> {
+    let mut value = serde_json::to_value(config)
+        .map_err(|e| AppError::io("序列化配置失败", io::Error::from(e)))?;
+    value["output_folder"] = serde_json::Value::from(output_folder);
+    value["log_level"] = serde_json::Value::from(config.python_log_level());
+
+    let mut file = tempfile::Builder::new()
+        .prefix(tempfiles::CONFIG_PREFIX)
+        .suffix(".json")
+        .tempfile_in(dir)
+        .map_err(|e| AppError::io("创建临时配置文件失败", e))?;
+    serde_json::to_writer_pretty(&mut file, &value)
+        .map_err(io::Error::from)
+        .and_then(|_| file.flush())
+        .map_err(|e| AppError::io("写入临时配置文件失败", e))?;
+    Ok(file)
+}
+
+// 旧版参数：逐项传递配置
+fn append_legacy_args(cmd: &mut Command, config: &ProcessConfig, output_folder: &str) {
+    cmd.arg("--input_folder").arg(&config.input_folder)
+       .arg("--output_folder").arg(output_folder)
+       .arg("--outlier_method").arg(&config.outlier_method)
+       .arg("--boxplot_threshold_discharge").arg(config.boxplot_threshold_discharge.to_string())
+       .arg("--boxplot_threshold_efficiency").arg(config.boxplot_threshold_efficiency.to_string())
+       .arg("--zscore_threshold_discharge").arg(config.zscore_threshold_discharge.to_string())
+       .arg("--zscore_threshold_efficiency").arg(config.zscore_threshold_efficiency.to_string())
+       .arg("--zscore_mad_constant").arg(config.zscore_mad_constant.to_string());
+
+    // 添加可选参数
+    if !config.reference_channel_method.is_empty() {
+        cmd.arg("--reference_channel_method").arg(&config.reference_channel_method);
+    }
+    if let Some(sheet) = config.cycle_sheet_name.as_deref().filter(|s| !s.is_empty()) {
+        cmd.arg("--cycle_sheet_name").arg(sheet);
+    }
+    if let Some(template) = &config.output_name_template {
+        cmd.arg("--output_name_template").arg(template);
+    }
+    if !config.files.is_empty() {
+        cmd.arg("--files").args(&config.files);
+    }
+}
+
+// 日志、进度与可复现性相关参数
+fn append_output_args(cmd: &mut Command, config: &ProcessConfig) {
+    if config.verbose {
+        cmd.arg("--verbose");
+    }
+    if config.enable_progress_bar {
+        cmd.arg("--enable_progress_bar");
+    }
+    if let Some(level) = config.python_log_level() {
+        cmd.arg("--log_level").arg(level);
+    }
+    if config.output_to_stdout {
+        cmd.arg("--output-stdout");
+    }
+    if config.deterministic {
+        cmd.arg("--deterministic");
+    }
+    if let Some(seed) = config.seed {
+        cmd.arg("--seed").arg(seed.to_string());
+    }
+}
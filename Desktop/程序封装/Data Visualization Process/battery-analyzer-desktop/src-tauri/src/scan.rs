@@ -0,0 +1,828 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, DirEntry, File, ReadDir};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::i18n::tr;
+use crate::paths;
+
+// 支持的数据文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileKind {
+    Xlsx,
+    Xls,
+    Csv,
+}
+
+impl FileKind {
+    // 按扩展名（最后一个点之后的部分）识别文件类型，不区分大小写；没有扩展名时为 None
+    pub fn from_path(path: &Path) -> Option<FileKind> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "xlsx" => Some(FileKind::Xlsx),
+            "xls" => Some(FileKind::Xls),
+            "csv" => Some(FileKind::Csv),
+            _ => None,
+        }
+    }
+
+    // 小写、不含点的扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileKind::Xlsx => "xlsx",
+            FileKind::Xls => "xls",
+            FileKind::Csv => "csv",
+        }
+    }
+
+    pub fn is_excel(self) -> bool {
+        matches!(self, FileKind::Xlsx | FileKind::Xls)
+    }
+
+    // 文件小于该大小时不可能是有效的数据文件（通常是导出失败留下的空文件）
+    pub fn min_valid_size(self) -> u64 {
+        if self.is_excel() {
+            MIN_EXCEL_BYTES
+        } else {
+            1
+        }
+    }
+}
+
+// changed_since 允许的时钟偏差（秒），同时覆盖FAT等文件系统2秒的时间精度
+const CLOCK_SKEW_SECS: u64 = 5;
+
+// 最小的有效工作簿也有数KB，小于该大小的Excel文件视为无效
+const MIN_EXCEL_BYTES: u64 = 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileInfo {
+    pub name: String,
+    pub path: String,
+    // 相对于扫描根目录的路径
+    pub relative_path: String,
+    pub size: u64,
+    // 便于显示的文件大小（1024进制，如 "812 KB"、"1.4 MB"）
+    pub size_human: String,
+    // 小写、不含点的扩展名（如 data.2024.XLSX 为 "xlsx"），与 kind 一致，前端无需再从 name 解析
+    #[serde(default)]
+    pub extension: String,
+    pub kind: FileKind,
+    pub is_excel: bool,
+    // 修改时间（UTC，RFC-3339格式），无法获取时为 None
+    pub last_modified: Option<String>,
+    // 修改时间的Unix时间戳（秒），便于排序和筛选
+    pub modified_unix: Option<u64>,
+    // 文件可能正在Excel等程序中打开（见 is_file_locked），仅在 check_locked 时检测，否则为 false
+    pub locked: bool,
+    // 文件内容的BLAKE3哈希（十六进制），仅在请求时计算
+    pub hash: Option<String>,
+    // 该条目是符号链接；不跟随链接时 size/修改时间为链接本身的信息
+    pub is_symlink: bool,
+}
+
+// 路径检查结果，供前端在选择后预先校验
+#[derive(Debug, Serialize)]
+pub struct PathInfo {
+    pub exists: bool,
+    pub is_dir: bool,
+    pub is_file: bool,
+}
+
+pub fn inspect_path(path: &str) -> PathInfo {
+    let metadata = fs::metadata(paths::extended(Path::new(path))).ok();
+    PathInfo {
+        exists: metadata.is_some(),
+        is_dir: metadata.as_ref().is_some_and(|metadata| metadata.is_dir()),
+        is_file: metadata.as_ref().is_some_and(|metadata| metadata.is_file()),
+    }
+}
+
+// 确认路径是已存在的文件夹：不存在时返回 DirectoryNotFound，存在但不是文件夹时返回 NotADirectory
+pub fn check_directory(path: &str) -> Result<(), AppError> {
+    let info = inspect_path(path);
+    if !info.exists {
+        Err(AppError::DirectoryNotFound(path.to_string()))
+    } else if !info.is_dir {
+        Err(AppError::NotADirectory(path.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+// 文件名通配符的匹配规则：不区分大小写（Windows和macOS的文件名不区分大小写）
+const PATTERN_MATCH: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+// 解析文件名通配符（glob语法，如 "*_cycle.xlsx"），未设置或为空时返回 None
+pub fn file_pattern(pattern: Option<&str>) -> Result<Option<Pattern>, AppError> {
+    let Some(pattern) = pattern.filter(|pattern| !pattern.trim().is_empty()) else {
+        return Ok(None);
+    };
+    Pattern::new(pattern).map(Some).map_err(|e| AppError::InvalidPattern {
+        pattern: pattern.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+// 文件名（不含目录）是否与通配符匹配
+pub fn matches_pattern(pattern: &Pattern, path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| pattern.matches_with(&name.to_string_lossy(), PATTERN_MATCH))
+}
+
+// 只保留文件名与通配符匹配的文件
+pub fn filter_by_pattern(mut files: Vec<FileInfo>, pattern: &Pattern) -> Vec<FileInfo> {
+    files.retain(|file| pattern.matches_with(&file.name, PATTERN_MATCH));
+    files
+}
+
+// 目录扫描选项
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ScanOptions {
+    pub recursive: bool,
+    // 递归时最多进入的子目录层数，未设置时不限制
+    pub max_depth: Option<usize>,
+    // 是否包含 ~$ 开头的Office临时/锁定文件和 . 开头的隐藏文件
+    pub include_temp: bool,
+    // 是否计算文件内容哈希（需要读取全部文件内容）
+    pub include_hash: bool,
+    // 是否检测Excel文件是否被占用（每个文件都要打开一次，网络共享上较慢）
+    pub check_locked: bool,
+    // 是否跟随符号链接：跟随时使用链接目标的信息，并递归进入指向目录的链接（已访问的目录不会重复进入）；
+    // 不跟随时报告链接本身的信息，且不进入链接指向的目录
+    pub follow_symlinks: bool,
+}
+
+// 文件列表排序字段
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub enum SortBy {
+    #[default]
+    Name,
+    Size,
+    Modified,
+}
+
+// 目录中数据文件的数量和总大小
+#[derive(Debug, Serialize)]
+pub struct DirectoryStats {
+    pub excel_count: usize,
+    pub csv_count: usize,
+    pub total_bytes: u64,
+    pub total_bytes_human: String,
+}
+
+// 内容相同的一组文件
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub size: u64,
+    pub files: Vec<FileInfo>,
+}
+
+// 分页后的目录列表，total_count 为分页前的文件总数
+#[derive(Debug, Serialize)]
+pub struct DirectoryPage {
+    pub files: Vec<FileInfo>,
+    pub total_count: usize,
+    pub offset: usize,
+    // 无法读取的条目（路径和原因），这些条目不在列表中
+    pub warnings: Vec<String>,
+}
+
+// 扫描到的数据文件，以及无法读取而被跳过的条目
+#[derive(Debug, Clone, Default)]
+pub struct ScanResult {
+    pub files: Vec<FileInfo>,
+    pub warnings: Vec<String>,
+}
+
+// 扫描进度事件名
+pub const SCAN_PROGRESS_EVENT: &str = "battery://scan-progress";
+// 每检查多少个目录条目报告一次进度
+const SCAN_PROGRESS_INTERVAL: usize = 500;
+// 并行读取文件元数据的线程数上限；网络共享上每次读取都要等待往返，多线程能明显缩短扫描时间
+const METADATA_WORKERS: usize = 16;
+// 数据文件少于该数量时在当前线程读取，不值得启动线程
+const PARALLEL_THRESHOLD: usize = 64;
+
+// 扫描进度：scanned 为已检查的目录条目数（含子目录和非数据文件），matched 为找到的数据文件数。
+// 扫描结束时 done 为 true
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanProgress {
+    // 扫描的根目录
+    pub path: String,
+    pub scanned: usize,
+    pub matched: usize,
+    pub done: bool,
+}
+
+// 扫描目录中的数据文件；Windows上使用扩展长度路径，支持超长路径和UNC网络共享。
+// 无法读取的条目跳过并写入日志
+pub fn scan_directory(root: &Path, options: &ScanOptions) -> Result<Vec<FileInfo>, AppError> {
+    let ScanResult { files, warnings } = scan_listing(root, options, &mut |_| {})?.result;
+    for warning in warnings {
+        log::warn!("{}", warning);
+    }
+    Ok(files)
+}
+
+// 扫描结果及扫描过的各目录在读取前的修改时间
+struct Listing {
+    result: ScanResult,
+    dirs: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+// 同 scan_directory，每检查 SCAN_PROGRESS_INTERVAL 个条目以及扫描结束时调用 on_progress
+fn scan_listing(
+    root: &Path,
+    options: &ScanOptions,
+    on_progress: &mut dyn FnMut(&ScanProgress),
+) -> Result<Listing, AppError> {
+    let root = &paths::extended(root);
+    let root_modified = dir_modified(root);
+    let entries = fs::read_dir(root).map_err(|e| AppError::io("读取文件夹失败", e))?;
+
+    // 先遍历目录收集数据文件，再并行读取元数据
+    let mut scanner = Scanner {
+        root,
+        options,
+        visited: HashSet::new(),
+        dirs: vec![(root.to_path_buf(), root_modified)],
+        candidates: Vec::new(),
+        warnings: Vec::new(),
+        scanned: 0,
+        on_progress,
+    };
+    if let Ok(canonical) = root.canonicalize() {
+        scanner.visited.insert(canonical);
+    }
+    scanner.visit_all(root, entries, 0);
+    let (files, failed) = collect_file_info(&scanner.candidates, root, options);
+    scanner.warnings.extend(failed);
+    scanner.report(true);
+    Ok(Listing {
+        result: ScanResult { files, warnings: scanner.warnings },
+        dirs: scanner.dirs,
+    })
+}
+
+fn dir_modified(dir: &Path) -> Option<SystemTime> {
+    fs::metadata(dir).and_then(|metadata| metadata.modified()).ok()
+}
+
+// 最多缓存的目录列表数，超出时淘汰最久未使用的
+const MAX_CACHED_LISTINGS: usize = 32;
+
+struct CachedListing {
+    listing: Listing,
+    used: Instant,
+}
+
+impl CachedListing {
+    // 扫描过的目录修改时间都未变化时仍然有效。
+    // 文件被原地覆盖时所在目录的修改时间不一定变化，此时需要强制刷新
+    fn is_fresh(&self) -> bool {
+        self.listing
+            .dirs
+            .iter()
+            .all(|(dir, modified)| modified.is_some() && dir_modified(dir) == *modified)
+    }
+}
+
+type ListingKey = (PathBuf, ScanOptions);
+
+// 正在进行的扫描；result 为 None 表示尚未结束，Some(None) 表示扫描失败
+#[derive(Default)]
+struct InFlight {
+    result: Mutex<Option<Option<ScanResult>>>,
+    done: Condvar,
+}
+
+impl InFlight {
+    fn wait(&self) -> Option<ScanResult> {
+        let mut result = self.result.lock().unwrap();
+        while result.is_none() {
+            result = self.done.wait(result).unwrap();
+        }
+        result.clone().flatten()
+    }
+
+    fn finish(&self, result: Option<ScanResult>) {
+        *self.result.lock().unwrap() = Some(result);
+        self.done.notify_all();
+    }
+}
+
+// 发起扫描的请求持有，离开作用域时（包括扫描中 panic）通知等待者并移除记录，
+// 避免等待者永远阻塞、后续请求一直等待已不存在的扫描
+struct FlightGuard<'a> {
+    in_flight: &'a Mutex<HashMap<ListingKey, Arc<InFlight>>>,
+    key: ListingKey,
+    flight: Arc<InFlight>,
+    // 扫描成功时的结果，为 None 时等待者自行重新扫描
+    result: Option<ScanResult>,
+}
+
+impl Drop for FlightGuard<'_> {
+    fn drop(&mut self) {
+        self.flight.finish(self.result.take());
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+// read_directory 的扫描结果缓存，按目录和扫描选项区分。
+// 同一目录的扫描正在进行时，新的请求等待并共用其结果，避免快速切换目录时在网络共享上重复扫描
+#[derive(Default)]
+pub struct DirectoryCache {
+    listings: Mutex<HashMap<ListingKey, CachedListing>>,
+    in_flight: Mutex<HashMap<ListingKey, Arc<InFlight>>>,
+}
+
+impl DirectoryCache {
+    // 同 scan_directory，同时报告进度并返回无法读取的条目；
+    // 目录未变化时直接返回缓存（只报告一次完成进度），refresh 为 true 时总是重新扫描
+    pub fn scan(
+        &self,
+        root: &Path,
+        options: &ScanOptions,
+        refresh: bool,
+        on_progress: &mut dyn FnMut(&ScanProgress),
+    ) -> Result<ScanResult, AppError> {
+        let key = (root.to_path_buf(), options.clone());
+        if !refresh {
+            let mut listings = self.listings.lock().unwrap();
+            if let Some(cached) = listings.get_mut(&key).filter(|cached| cached.is_fresh()) {
+                cached.used = Instant::now();
+                let result = cached.listing.result.clone();
+                drop(listings);
+                report_done(root, &result.files, on_progress);
+                return Ok(result);
+            }
+        }
+
+        // 已有相同的扫描在进行时等待其结果（refresh 请求同样共用，该扫描开始时间晚于缓存）；
+        // 该扫描失败时自行重新扫描，以得到具体的错误
+        let (flight, leader) = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(flight) => (flight.clone(), false),
+                None => {
+                    let flight = Arc::new(InFlight::default());
+                    in_flight.insert(key.clone(), flight.clone());
+                    (flight, true)
+                }
+            }
+        };
+        if !leader {
+            if let Some(result) = flight.wait() {
+                report_done(root, &result.files, on_progress);
+                return Ok(result);
+            }
+            return scan_listing(root, options, on_progress).map(|listing| listing.result);
+        }
+
+        // 扫描期间不持有锁，其他目录的读取不受影响
+        let mut guard = FlightGuard { in_flight: &self.in_flight, key: key.clone(), flight, result: None };
+        let scanned = scan_listing(root, options, on_progress);
+        guard.result = scanned.as_ref().ok().map(|listing| listing.result.clone());
+        drop(guard);
+        let listing = scanned?;
+        let result = listing.result.clone();
+        let mut listings = self.listings.lock().unwrap();
+        if listings.len() >= MAX_CACHED_LISTINGS && !listings.contains_key(&key) {
+            let oldest = listings
+                .iter()
+                .min_by_key(|(_, cached)| cached.used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                listings.remove(&oldest);
+            }
+        }
+        listings.insert(key, CachedListing { listing, used: Instant::now() });
+        Ok(result)
+    }
+}
+
+// 未实际扫描（使用缓存或共用其他请求的结果）时只报告一次完成进度
+fn report_done(root: &Path, files: &[FileInfo], on_progress: &mut dyn FnMut(&ScanProgress)) {
+    on_progress(&ScanProgress {
+        path: paths::display(root),
+        scanned: files.len(),
+        matched: files.len(),
+        done: true,
+    });
+}
+
+// 按遍历顺序读取各候选文件的信息，输出顺序与并行程度无关。
+// 单个文件读取失败时跳过并返回警告，不影响其余文件
+fn collect_file_info(
+    candidates: &[Candidate],
+    root: &Path,
+    options: &ScanOptions,
+) -> (Vec<FileInfo>, Vec<String>) {
+    let total = candidates.len();
+    let results: Vec<Option<Result<FileInfo, String>>> = if total < PARALLEL_THRESHOLD {
+        candidates.iter().map(|candidate| Some(file_info(candidate, root, options))).collect()
+    } else {
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new((0..total).map(|_| None).collect::<Vec<_>>());
+        thread::scope(|scope| {
+            for _ in 0..METADATA_WORKERS.min(total) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= total {
+                        break;
+                    }
+                    let info = file_info(&candidates[index], root, options);
+                    results.lock().unwrap()[index] = Some(info);
+                });
+            }
+        });
+        results.into_inner().unwrap()
+    };
+    let mut files = Vec::with_capacity(total);
+    let mut warnings = Vec::new();
+    for result in results.into_iter().flatten() {
+        match result {
+            Ok(info) => files.push(info),
+            Err(warning) => warnings.push(warning),
+        }
+    }
+    (files, warnings)
+}
+
+// 无法读取的条目的警告：路径和原因
+fn read_warning(path: &Path, e: &io::Error) -> String {
+    tr!("无法读取 {}: {}", "Cannot read {}: {}", paths::display(path), e)
+}
+
+fn file_info(candidate: &Candidate, root: &Path, options: &ScanOptions) -> Result<FileInfo, String> {
+    let Candidate { entry, path, file_name, kind, is_symlink } = candidate;
+    // DirEntry::metadata 不跟随符号链接（Windows上直接取自目录列表）；
+    // 跟随时读取目标的信息，链接失效时退回链接本身
+    let metadata = if *is_symlink && options.follow_symlinks {
+        fs::metadata(path).or_else(|_| entry.metadata())
+    } else {
+        entry.metadata()
+    };
+    let metadata = metadata.map_err(|e| read_warning(path, &e))?;
+    let size = metadata.len();
+    let modified = metadata.modified().ok();
+
+    Ok(FileInfo {
+        name: file_name.clone(),
+        path: paths::display(path),
+        relative_path: relative_to(path, root),
+        size,
+        size_human: format_size(size),
+        extension: kind.extension().to_string(),
+        kind: *kind,
+        is_excel: kind.is_excel(),
+        last_modified: modified.map(to_rfc3339),
+        modified_unix: modified.and_then(to_unix_secs),
+        locked: options.check_locked && kind.is_excel() && is_file_locked(path),
+        // 无法读取的文件不计算哈希
+        hash: if options.include_hash { hash_file(path).ok() } else { None },
+        is_symlink: *is_symlink,
+    })
+}
+
+// 只保留 since_unix 之后新增或修改的文件。网络共享等文件服务器的时钟可能与本机有偏差，
+// 因此放宽 CLOCK_SKEW_SECS；修改时间晚于当前时间或无法获取修改时间的文件一律视为已变化
+pub fn changed_since(mut files: Vec<FileInfo>, since_unix: u64) -> Vec<FileInfo> {
+    let threshold = since_unix.saturating_sub(CLOCK_SKEW_SECS);
+    files.retain(|file| file.modified_unix.map_or(true, |modified| modified >= threshold));
+    files
+}
+
+// 排序后截取一页，limit 未设置时返回 offset 之后的全部文件
+pub fn paginate(
+    ScanResult { mut files, warnings }: ScanResult,
+    sort_by: SortBy,
+    descending: bool,
+    offset: usize,
+    limit: Option<usize>,
+) -> DirectoryPage {
+    files.sort_by(|a, b| {
+        let ordering = match sort_by {
+            SortBy::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortBy::Size => a.size.cmp(&b.size),
+            SortBy::Modified => a.modified_unix.cmp(&b.modified_unix),
+        };
+        // 相同时按相对路径排序，保证分页结果稳定
+        let ordering = ordering.then_with(|| a.relative_path.cmp(&b.relative_path));
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    let total_count = files.len();
+    let files = files
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    DirectoryPage { files, total_count, offset, warnings }
+}
+
+// 统计目录中的数据文件，过滤规则与 read_directory 相同
+pub fn directory_stats(root: &Path, options: &ScanOptions) -> Result<DirectoryStats, AppError> {
+    let files = scan_directory(root, options)?;
+    let excel_count = files.iter().filter(|file| file.is_excel).count();
+    let total_bytes = files.iter().map(|file| file.size).sum();
+    Ok(DirectoryStats {
+        excel_count,
+        csv_count: files.len() - excel_count,
+        total_bytes,
+        total_bytes_human: format_size(total_bytes),
+    })
+}
+
+// 查找内容重复的文件：先按大小分组，只对大小相同的文件计算哈希。
+// 空文件不参与比较
+pub fn find_duplicates(root: &Path, options: &ScanOptions) -> Result<Vec<DuplicateGroup>, AppError> {
+    let mut by_size: HashMap<u64, Vec<FileInfo>> = HashMap::new();
+    for file in scan_directory(root, options)? {
+        if file.size > 0 {
+            by_size.entry(file.size).or_default().push(file);
+        }
+    }
+
+    let mut by_hash: HashMap<String, Vec<FileInfo>> = HashMap::new();
+    for mut file in by_size.into_values().filter(|files| files.len() > 1).flatten() {
+        let Ok(hash) = hash_file(&paths::extended(Path::new(&file.path))) else { continue };
+        file.hash = Some(hash.clone());
+        by_hash.entry(hash).or_default().push(file);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(hash, mut files)| {
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            DuplicateGroup { hash, size: files[0].size, files }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.files[0].path.cmp(&b.files[0].path));
+    Ok(groups)
+}
+
+// 流式计算文件内容的BLAKE3哈希，大文件不会整体读入内存
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(File::open(path)?)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+struct Scanner<'a> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    // 已访问目录的规范路径，防止符号链接形成环路导致无限递归
+    visited: HashSet<PathBuf>,
+    // 进入过的目录及读取前的修改时间，用于判断缓存是否有效
+    dirs: Vec<(PathBuf, Option<SystemTime>)>,
+    // 遍历时找到的数据文件，元数据稍后并行读取
+    candidates: Vec<Candidate>,
+    // 无法读取的条目和子目录
+    warnings: Vec<String>,
+    scanned: usize,
+    on_progress: &'a mut dyn FnMut(&ScanProgress),
+}
+
+// 遍历目录时找到的数据文件
+struct Candidate {
+    entry: DirEntry,
+    path: PathBuf,
+    file_name: String,
+    kind: FileKind,
+    is_symlink: bool,
+}
+
+impl Scanner<'_> {
+    fn visit_all(&mut self, dir: &Path, entries: ReadDir, depth: usize) {
+        for entry in entries {
+            match entry {
+                Ok(entry) => self.visit(entry, depth),
+                Err(e) => self.warnings.push(read_warning(dir, &e)),
+            }
+        }
+    }
+
+    fn visit(&mut self, entry: DirEntry, depth: usize) {
+        self.scanned += 1;
+        if self.scanned % SCAN_PROGRESS_INTERVAL == 0 {
+            self.report(false);
+        }
+
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if !self.options.include_temp && is_temp_or_hidden(&file_name) {
+            return;
+        }
+
+        // DirEntry::file_type 在Windows上来自目录列表本身，不需要额外读取；
+        // 只有跟随符号链接时才需要读取链接目标判断是否为目录
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(e) => return self.warnings.push(read_warning(&path, &e)),
+        };
+        let is_symlink = file_type.is_symlink();
+        let is_dir = if is_symlink {
+            self.options.follow_symlinks && path.is_dir()
+        } else {
+            file_type.is_dir()
+        };
+        if is_dir {
+            if self.should_descend(depth) {
+                self.descend(&path, depth + 1);
+            }
+            return;
+        }
+
+        // 只包含Excel和CSV文件
+        if let Some(kind) = FileKind::from_path(&path) {
+            self.candidates.push(Candidate { entry, path, file_name, kind, is_symlink });
+        }
+    }
+
+    fn report(&mut self, done: bool) {
+        let progress = ScanProgress {
+            path: paths::display(self.root),
+            scanned: self.scanned,
+            matched: self.candidates.len(),
+            done,
+        };
+        (self.on_progress)(&progress);
+    }
+
+    fn should_descend(&self, depth: usize) -> bool {
+        self.options.recursive && self.options.max_depth.map_or(true, |max| depth < max)
+    }
+
+    fn descend(&mut self, dir: &Path, depth: usize) {
+        let Ok(canonical) = dir.canonicalize() else { return };
+        if !self.visited.insert(canonical) {
+            return;
+        }
+
+        // 子目录无法读取时跳过并记录警告，不影响其余文件
+        let modified = dir_modified(dir);
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => return self.warnings.push(read_warning(dir, &e)),
+        };
+        self.dirs.push((dir.to_path_buf(), modified));
+        self.visit_all(dir, entries, depth);
+    }
+}
+
+// Office打开文件时会创建 ~$ 开头的锁定文件
+pub fn is_temp_or_hidden(file_name: &str) -> bool {
+    file_name.starts_with("~$") || file_name.starts_with('.')
+}
+
+// 判断文件是否正被其他程序占用：同目录下存在Excel的 ~$ 锁定文件，
+// 或者文件被独占打开（Windows）/ 持有 flock 排他锁（Unix）
+pub fn is_file_locked(path: &Path) -> bool {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    has_owner_file(path, &file_name) || has_os_lock(path)
+}
+
+// Windows上以不共享方式打开文件，被其他进程打开时返回共享冲突
+#[cfg(windows)]
+fn has_os_lock(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // ERROR_SHARING_VIOLATION、ERROR_LOCK_VIOLATION
+    match fs::OpenOptions::new().read(true).share_mode(0).open(path) {
+        Ok(_) => false,
+        Err(e) => matches!(e.raw_os_error(), Some(32) | Some(33)),
+    }
+}
+
+// Unix上的文件锁都是建议性的，只能检测到其他进程持有的 flock 排他锁；
+// 尝试加共享锁，成功则立即释放
+#[cfg(unix)]
+fn has_os_lock(path: &Path) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    let Ok(file) = File::open(path) else { return false };
+    let fd = file.as_raw_fd();
+    if unsafe { libc::flock(fd, libc::LOCK_SH | libc::LOCK_NB) } == 0 {
+        unsafe { libc::flock(fd, libc::LOCK_UN) };
+        return false;
+    }
+    io::Error::last_os_error().raw_os_error() == Some(libc::EWOULDBLOCK)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn has_os_lock(_path: &Path) -> bool {
+    false
+}
+
+// 检查Excel的锁定文件，只认该文件对应的确切名称（见 owner_file_names），
+// 其他文件的锁定文件即使后缀相同也不算
+fn has_owner_file(path: &Path, file_name: &str) -> bool {
+    owner_file_names(file_name)
+        .into_iter()
+        .any(|name| path.with_file_name(name).exists())
+}
+
+// 锁定文件的名称：Excel为 "~$" + 完整文件名；文件名（不含扩展名）为7个字符时Office用 "~$" 替换第一个字符，
+// 8个字符及以上时替换前两个字符
+fn owner_file_names(file_name: &str) -> Vec<String> {
+    let mut names = vec![format!("~${}", file_name)];
+    let stem_len = Path::new(file_name)
+        .file_stem()
+        .map_or(0, |stem| stem.to_string_lossy().chars().count());
+    let dropped = match stem_len {
+        0..=6 => return names,
+        7 => 1,
+        _ => 2,
+    };
+    names.push(format!("~${}", file_name.chars().skip(dropped).collect::<String>()));
+    names
+}
+
+// 按1024进制格式化文件大小，小于10时保留一位小数
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        format!("{:.1} {}", value, UNITS[unit])
+    } else {
+        format!("{:.0} {}", value, UNITS[unit])
+    }
+}
+
+fn to_rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn to_unix_secs(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+fn relative_to(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_file_names_follow_office_truncation() {
+        assert_eq!(owner_file_names("A1.xlsx"), vec!["~$A1.xlsx"]);
+        assert_eq!(owner_file_names("ABCDEFG.xlsx"), vec!["~$ABCDEFG.xlsx", "~$BCDEFG.xlsx"]);
+        assert_eq!(
+            owner_file_names("20240101_A1.xlsx"),
+            vec!["~$20240101_A1.xlsx", "~$240101_A1.xlsx"]
+        );
+    }
+
+    #[test]
+    fn owner_file_of_other_file_is_not_a_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let short = dir.path().join("XY1.xlsx");
+        let long = dir.path().join("20240101_A1.xlsx");
+        fs::write(&short, b"data").unwrap();
+        fs::write(&long, b"data").unwrap();
+        // "1.xlsx" 的锁定文件，与 XY1.xlsx 去掉前两个字符后相同
+        fs::write(dir.path().join("~$1.xlsx"), b"").unwrap();
+        assert!(!has_owner_file(&short, "XY1.xlsx"));
+        assert!(!has_owner_file(&long, "20240101_A1.xlsx"));
+
+        fs::write(dir.path().join("~$240101_A1.xlsx"), b"").unwrap();
+        assert!(has_owner_file(&long, "20240101_A1.xlsx"));
+    }
+}
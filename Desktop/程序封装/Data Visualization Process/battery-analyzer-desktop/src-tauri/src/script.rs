@@ -0,0 +1,45 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppError;
+
+// 指定分析脚本路径的环境变量
+const SCRIPT_ENV_VAR: &str = "PYTHON_SCRIPT_PATH";
+// 打包后脚本在资源目录中的位置（见 tauri.conf.json 的 bundle.resources）
+const BUNDLED_SCRIPT: &str = "python/main.py";
+
+// 定位分析脚本，优先级：配置中的 script_path > PYTHON_SCRIPT_PATH 环境变量 > 打包资源 > 开发目录
+pub fn resolve_script(app: &AppHandle, override_path: Option<&str>) -> Result<PathBuf, AppError> {
+    let explicit = override_path
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os(SCRIPT_ENV_VAR).map(PathBuf::from));
+    if let Some(path) = explicit {
+        return check_exists(vec![path]);
+    }
+
+    let mut candidates = Vec::new();
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        candidates.push(resource_dir.join(BUNDLED_SCRIPT));
+    }
+    // 开发模式下直接使用仓库中的脚本
+    if cfg!(debug_assertions) {
+        candidates.push(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../main.py"));
+    }
+    check_exists(candidates)
+}
+
+fn check_exists(candidates: Vec<PathBuf>) -> Result<PathBuf, AppError> {
+    if let Some(found) = candidates.iter().find(|path| path.is_file()) {
+        return Ok(found.clone());
+    }
+    Err(AppError::ScriptNotFound(
+        candidates
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+    ))
+}
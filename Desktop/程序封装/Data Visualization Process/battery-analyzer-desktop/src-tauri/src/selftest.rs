@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ProcessConfig;
+use crate::error::AppError;
+use crate::i18n::tr;
+use crate::runner::{self, ProcessOutcome};
+use crate::report::RESULT_PREFIX;
+use crate::{python, script};
+
+// 打包的示例数据在资源目录中的位置（见 tauri.conf.json 的 bundle.resources）
+const BUNDLED_SAMPLES: &str = "self-test";
+// 示例数据很小，超过该时间视为卡住
+const TIMEOUT_SECS: u64 = 300;
+
+// 自检的单个步骤
+#[derive(Debug, Serialize)]
+pub struct SelfTestStep {
+    // python / script / sample / run / output
+    pub name: &'static str,
+    pub passed: bool,
+    // 通过时为说明信息（如找到的路径），失败时为原因
+    pub detail: String,
+}
+
+// 自检结果，前一步失败时不再执行后续步骤
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub steps: Vec<SelfTestStep>,
+}
+
+impl SelfTestReport {
+    fn step(&mut self, name: &'static str, result: Result<String, String>) -> bool {
+        let passed = result.is_ok();
+        let detail = result.unwrap_or_else(|e| e);
+        self.steps.push(SelfTestStep { name, passed, detail });
+        self.passed = passed;
+        passed
+    }
+}
+
+// 用默认配置处理打包的示例数据，检查Python、分析脚本和输出是否正常。
+// 示例数据复制到临时目录后处理，临时目录在返回时删除
+pub fn run(app: &AppHandle) -> Result<SelfTestReport, AppError> {
+    let mut report = SelfTestReport { passed: false, steps: Vec::new() };
+
+    let python = python::resolve_runtime(None, None, None)
+        .map(|runtime| runtime.executable.to_string_lossy().to_string())
+        .map_err(|e| AppError::from(e).to_string());
+    if !report.step("python", python) {
+        return Ok(report);
+    }
+
+    let script = script::resolve_script(app, None)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string());
+    if !report.step("script", script) {
+        return Ok(report);
+    }
+
+    let temp = tempfile::Builder::new()
+        .prefix("battery-analyzer-self-test-")
+        .tempdir()
+        .map_err(|e| AppError::io("创建自检临时目录失败", e))?;
+    let input = temp.path().join("input");
+    let output = temp.path().join("output");
+    if !report.step("sample", copy_samples(app, &input)) {
+        return Ok(report);
+    }
+
+    let mut config = ProcessConfig::with_defaults(
+        input.to_string_lossy().to_string(),
+        output.to_string_lossy().to_string(),
+    );
+    config.timeout_secs = Some(TIMEOUT_SECS);
+    let result = match runner::execute(app, config) {
+        Ok(ProcessOutcome::Run(result)) => result,
+        // 默认配置不是试运行，不会返回处理计划
+        Ok(ProcessOutcome::Plan(_)) => return Ok(report),
+        Err(e) => {
+            report.step("run", Err(e.to_string()));
+            return Ok(report);
+        }
+    };
+    report.step("run", Ok(tr!("分析脚本运行完成", "Analysis script finished")));
+
+    // 自检使用默认文件名，运行报告的输出文件中应有汇总工作簿
+    let produced = result
+        .output_files()
+        .iter()
+        .find(|file| file.name.starts_with(RESULT_PREFIX) && file.name.ends_with(".xlsx"))
+        .map(|file| file.name.clone())
+        .ok_or_else(|| {
+            tr!(
+                "输出文件夹中没有生成 {}*.xlsx",
+                "No {}*.xlsx was produced in the output folder",
+                RESULT_PREFIX
+            )
+        });
+    report.step("output", produced);
+    Ok(report)
+}
+
+// 将示例数据复制到 input 目录，返回复制的文件数说明
+fn copy_samples(app: &AppHandle, input: &Path) -> Result<String, String> {
+    let source = sample_dir(app).ok_or_else(|| tr!("未找到示例数据", "Sample data not found"))?;
+    fs::create_dir_all(input).map_err(|e| e.to_string())?;
+
+    let mut copied = 0;
+    for entry in fs::read_dir(&source).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx")) {
+            fs::copy(&path, input.join(entry.file_name())).map_err(|e| e.to_string())?;
+            copied += 1;
+        }
+    }
+    if copied == 0 {
+        return Err(tr!(
+            "示例数据目录中没有Excel文件: {}",
+            "No Excel files in the sample folder: {}",
+            source.display()
+        ));
+    }
+    Ok(tr!(
+        "已复制 {} 个示例文件（{}）",
+        "Copied {} sample files ({})",
+        copied,
+        source.display()
+    ))
+}
+
+// 优先使用打包资源，开发模式下使用仓库中的示例数据
+fn sample_dir(app: &AppHandle) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        candidates.push(resource_dir.join(BUNDLED_SAMPLES));
+    }
+    if cfg!(debug_assertions) {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        candidates.push(manifest_dir.join("resources").join(BUNDLED_SAMPLES));
+    }
+    candidates.into_iter().find(|dir| dir.is_dir())
+}
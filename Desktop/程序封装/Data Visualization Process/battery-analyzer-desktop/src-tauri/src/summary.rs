@@ -0,0 +1,68 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+use crate::scan;
+
+// 导出结果后写在输出文件夹中的摘要文件（见 main_processor.py 的 _write_summary）
+pub const SUMMARY_FILE: &str = "summary.json";
+
+// 运行摘要的关键数值，供结果总览显示。旧版脚本可能只写入部分字段，
+// 缺少或类型不符的字段为 None，不影响其他字段
+#[derive(Debug, Serialize)]
+pub struct RunSummary {
+    pub files_processed: Option<u64>,
+    pub files_failed: Option<u64>,
+    // 当前脚本不写入该字段
+    pub cycles: Option<u64>,
+    // 异常检测剔除的数据点数
+    pub outliers_removed: Option<u64>,
+    // 平均库仑效率（%）
+    pub mean_efficiency: Option<f64>,
+    // 平均放电比容量（mAh/g）
+    pub mean_discharge_capacity: Option<f64>,
+    pub outlier_method: Option<String>,
+    pub reference_channel: Option<String>,
+}
+
+// 读取结果文件夹中的 summary.json；文件不存在时返回 FileNotFound，不是JSON对象时返回解析错误
+pub fn read_summary(folder: &str) -> Result<RunSummary, AppError> {
+    scan::check_directory(folder)?;
+    let path = Path::new(folder).join(SUMMARY_FILE);
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(AppError::FileNotFound(path.to_string_lossy().to_string()))
+        }
+        Err(e) => return Err(AppError::io("读取运行摘要失败", e)),
+    };
+    let value: Value =
+        serde_json::from_str(&json).map_err(|e| AppError::io("解析运行摘要失败", io::Error::from(e)))?;
+    let Value::Object(fields) = value else {
+        return Err(AppError::io(
+            "解析运行摘要失败",
+            io::Error::new(io::ErrorKind::InvalidData, "summary.json is not a JSON object"),
+        ));
+    };
+
+    let count = |name: &str| fields.get(name).and_then(Value::as_u64);
+    let number = |name: &str| fields.get(name).and_then(Value::as_f64);
+    Ok(RunSummary {
+        files_processed: count("files_processed"),
+        files_failed: count("files_failed"),
+        cycles: count("cycles"),
+        outliers_removed: count("outliers_removed"),
+        mean_efficiency: number("mean_efficiency"),
+        mean_discharge_capacity: number("mean_discharge_capacity"),
+        outlier_method: text(&fields, "outlier_method"),
+        reference_channel: text(&fields, "reference_channel"),
+    })
+}
+
+fn text(fields: &Map<String, Value>, name: &str) -> Option<String> {
+    fields.get(name).and_then(Value::as_str).map(str::to_string)
+}
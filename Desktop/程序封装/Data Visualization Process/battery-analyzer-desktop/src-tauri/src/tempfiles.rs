@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppError;
+
+// 传给Python的临时配置文件的文件名前缀
+pub const CONFIG_PREFIX: &str = "battery-analyzer-";
+// 修改时间早于该时长的临时文件视为上次异常退出时遗留的文件
+const STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+// 临时配置文件专用的目录：应用缓存目录下的 tmp 子目录，无法定位缓存目录时为系统临时目录下的 battery-analyzer
+fn temp_dir(app: &AppHandle) -> PathBuf {
+    match app.path().app_cache_dir() {
+        Ok(dir) => dir.join("tmp"),
+        Err(_) => std::env::temp_dir().join("battery-analyzer"),
+    }
+}
+
+// 创建临时目录并删除其中遗留的临时配置文件，应用启动和每次分析开始时调用。
+// 正常运行结束时临时文件会自动删除，只有应用崩溃或被强制结束时才会遗留
+pub fn prepare(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = temp_dir(app);
+    fs::create_dir_all(&dir).map_err(|e| AppError::io("创建临时目录失败", e))?;
+    remove_stale(&dir);
+    Ok(dir)
+}
+
+// 只删除带 CONFIG_PREFIX 前缀且足够旧的文件，其他文件和正在运行的任务使用的文件不受影响
+fn remove_stale(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with(CONFIG_PREFIX) {
+            continue;
+        }
+        let stale = entry.metadata().is_ok_and(|metadata| {
+            metadata.is_file()
+                && metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .is_some_and(|age| age > STALE_AFTER)
+        });
+        if stale {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                log::warn!("删除遗留的临时文件失败: {}: {}", entry.path().display(), e);
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::csvfile;
+use crate::error::AppError;
+use crate::excel;
+use crate::scan::FileKind;
+
+// 最多读取表头的工作表数，仪器导出的工作簿通常只有几个工作表
+const MAX_SHEETS: usize = 8;
+// 至少匹配的特征数，只匹配一项（如名为 Cycle 的工作表）不足以判断
+const MIN_MATCHES: usize = 2;
+// 匹配该数量的特征即视为完全确定
+const FULL_CONFIDENCE_MATCHES: usize = 4;
+
+// 导出数据文件的测试仪器厂商
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Vendor {
+    // 新威（BTSDA导出），分析脚本默认读取的格式
+    Neware,
+    Arbin,
+    // 蓝电（LANDdt导出）
+    Land,
+    // EC-Lab导出的文本/CSV
+    BioLogic,
+    Unknown,
+}
+
+// 各厂商导出文件的特征：工作表名和列名中包含的片段（小写）
+struct Signature {
+    vendor: Vendor,
+    sheets: &'static [&'static str],
+    headers: &'static [&'static str],
+    // 建议的异常检测方法和参考通道选择方法
+    outlier_method: &'static str,
+    reference_channel_method: &'static str,
+}
+
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        vendor: Vendor::Neware,
+        sheets: &["cycle", "statis", "record", "step"],
+        headers: &["充电比容量(mah/g)", "放电比容量(mah/g)", "放电中值电压(v)", "循环号"],
+        outlier_method: "boxplot",
+        reference_channel_method: "traditional",
+    },
+    Signature {
+        vendor: Vendor::Arbin,
+        sheets: &["global_info", "channel_", "statistics_"],
+        headers: &[
+            "data_point",
+            "test_time(s)",
+            "cycle_index",
+            "charge_capacity(ah)",
+            "discharge_capacity(ah)",
+        ],
+        outlier_method: "zscore_mad",
+        reference_channel_method: "pca",
+    },
+    Signature {
+        vendor: Vendor::Land,
+        sheets: &["cycle-tab", "step-tab", "record-tab"],
+        headers: &["capc/mah", "capd/mah", "specapc/mah/g", "specapd/mah/g"],
+        outlier_method: "boxplot",
+        reference_channel_method: "traditional",
+    },
+    Signature {
+        vendor: Vendor::BioLogic,
+        sheets: &[],
+        headers: &[
+            "ewe/v",
+            "cycle number",
+            "ox/red",
+            "q charge/discharge/ma.h",
+            "time/s",
+            "control/v/ma",
+        ],
+        outlier_method: "zscore_mad",
+        reference_channel_method: "retention_curve_mse",
+    },
+];
+
+// 数据格式识别结果
+#[derive(Debug, Serialize)]
+pub struct FormatDetection {
+    pub vendor: Vendor,
+    // 0到1，Unknown 时为0
+    pub confidence: f64,
+    // 匹配到的工作表名和列名
+    pub matched: Vec<String>,
+    // 建议的设置，Unknown 时为 None
+    pub outlier_method: Option<String>,
+    pub reference_channel_method: Option<String>,
+}
+
+// 根据工作表名和各工作表的表头判断文件由哪种仪器导出（只读取表头，不读取数据行）
+pub fn detect_format(path: &str) -> Result<FormatDetection, AppError> {
+    let (sheets, headers) = match FileKind::from_path(Path::new(path)) {
+        Some(FileKind::Csv) => (Vec::new(), csvfile::read_headers(path)?),
+        _ => {
+            let sheets = excel::list_sheets(path)?;
+            // 单个工作表无法读取时跳过，其余工作表仍可用于判断
+            let headers = sheets
+                .iter()
+                .take(MAX_SHEETS)
+                .filter_map(|sheet| excel::read_headers(path, Some(sheet)).ok())
+                .flatten()
+                .collect();
+            (sheets, headers)
+        }
+    };
+    let sheets: Vec<String> = sheets.iter().map(|sheet| sheet.to_lowercase()).collect();
+    let headers: Vec<String> = headers.iter().map(|header| header.trim().to_lowercase()).collect();
+
+    let best = SIGNATURES
+        .iter()
+        .map(|signature| {
+            let matched_sheets = signature
+                .sheets
+                .iter()
+                .filter(|marker| sheets.iter().any(|sheet| sheet.contains(*marker)));
+            let matched_headers = signature
+                .headers
+                .iter()
+                .filter(|marker| headers.iter().any(|header| header.contains(*marker)));
+            let matched: Vec<String> =
+                matched_sheets.chain(matched_headers).map(|marker| marker.to_string()).collect();
+            (signature, matched)
+        })
+        // max_by_key 在匹配数相同时取最后一个，反向遍历使先列出的厂商优先
+        .rev()
+        .max_by_key(|(_, matched)| matched.len());
+
+    Ok(match best {
+        Some((signature, matched)) if matched.len() >= MIN_MATCHES => FormatDetection {
+            vendor: signature.vendor,
+            confidence: (matched.len() as f64 / FULL_CONFIDENCE_MATCHES as f64).min(1.0),
+            matched,
+            outlier_method: Some(signature.outlier_method.to_string()),
+            reference_channel_method: Some(signature.reference_channel_method.to_string()),
+        },
+        _ => FormatDetection {
+            vendor: Vendor::Unknown,
+            confidence: 0.0,
+            matched: Vec::new(),
+            outlier_method: None,
+            reference_channel_method: None,
+        },
+    })
+}
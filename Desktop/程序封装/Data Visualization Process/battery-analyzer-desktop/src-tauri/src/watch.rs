@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppError;
+use crate::scan::{self, FileKind};
+
+// 文件夹变化事件名
+const FS_CHANGE_EVENT: &str = "battery://fs-change";
+// 最后一次变化后等待的时间，文件写入过程中的连续事件合并为一次通知
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ChangeKind {
+    Created,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FileChange {
+    path: String,
+    kind: ChangeKind,
+}
+
+// 文件夹变化事件负载：一次防抖周期内的全部变化
+#[derive(Debug, Clone, Serialize)]
+struct FsChangeEvent {
+    directory: String,
+    changes: Vec<FileChange>,
+}
+
+// 正在监听的文件夹，watcher 被移除（drop）后后台线程自动退出
+#[derive(Default)]
+pub struct WatcherRegistry {
+    watchers: Mutex<HashMap<PathBuf, RecommendedWatcher>>,
+}
+
+impl WatcherRegistry {
+    // 开始监听文件夹，已在监听时不重复创建
+    pub fn watch(&self, app: &AppHandle, path: &str, recursive: bool) -> Result<(), AppError> {
+        scan::check_directory(path)?;
+        let dir = Path::new(path);
+
+        let mut watchers = self.watchers.lock().unwrap();
+        if watchers.contains_key(dir) {
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .map_err(|e| watch_failed(path, e))?;
+        let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+        watcher.watch(dir, mode).map_err(|e| watch_failed(path, e))?;
+
+        let app = app.clone();
+        let directory = path.to_string();
+        thread::spawn(move || debounce_loop(&app, &directory, rx));
+
+        watchers.insert(dir.to_path_buf(), watcher);
+        Ok(())
+    }
+
+    // 停止监听，返回该文件夹之前是否在监听
+    pub fn unwatch(&self, path: &str) -> bool {
+        self.watchers.lock().unwrap().remove(Path::new(path)).is_some()
+    }
+
+    // 停止全部监听（应用退出时调用）
+    pub fn clear(&self) {
+        self.watchers.lock().unwrap().clear();
+    }
+}
+
+// 收集变化直到 DEBOUNCE 时间内没有新事件，再一次性发送给前端
+fn debounce_loop(app: &AppHandle, directory: &str, rx: mpsc::Receiver<Event>) {
+    let mut pending: HashMap<PathBuf, ChangeKind> = HashMap::new();
+    loop {
+        let received = if pending.is_empty() {
+            rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            rx.recv_timeout(DEBOUNCE)
+        };
+        match received {
+            Ok(event) => record(&mut pending, event),
+            Err(RecvTimeoutError::Timeout) => flush(app, directory, &mut pending),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+fn record(pending: &mut HashMap<PathBuf, ChangeKind>, event: Event) {
+    for path in event.paths {
+        if !is_data_file(&path) {
+            continue;
+        }
+        // 重命名等事件不区分方向，按文件当前是否存在判断
+        let kind = match event.kind {
+            EventKind::Create(_) => ChangeKind::Created,
+            EventKind::Remove(_) => ChangeKind::Removed,
+            EventKind::Modify(_) | EventKind::Any if !path.exists() => ChangeKind::Removed,
+            EventKind::Modify(_) | EventKind::Any => ChangeKind::Modified,
+            _ => continue,
+        };
+        // 同一周期内新建后又写入的文件仍报告为新建
+        let merged = match (pending.get(&path), kind) {
+            (Some(ChangeKind::Created), ChangeKind::Modified) => ChangeKind::Created,
+            (Some(ChangeKind::Removed), ChangeKind::Modified) => ChangeKind::Created,
+            _ => kind,
+        };
+        pending.insert(path, merged);
+    }
+}
+
+fn flush(app: &AppHandle, directory: &str, pending: &mut HashMap<PathBuf, ChangeKind>) {
+    let mut changes: Vec<FileChange> = pending
+        .drain()
+        .map(|(path, kind)| FileChange { path: path.to_string_lossy().to_string(), kind })
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    let _ = app.emit(
+        FS_CHANGE_EVENT,
+        FsChangeEvent { directory: directory.to_string(), changes },
+    );
+}
+
+// 只关注Excel/CSV数据文件，忽略Office临时文件和隐藏文件
+fn is_data_file(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    !scan::is_temp_or_hidden(&name) && FileKind::from_path(path).is_some()
+}
+
+fn watch_failed(path: &str, e: notify::Error) -> AppError {
+    AppError::WatchFailed { path: path.to_string(), reason: e.to_string() }
+}
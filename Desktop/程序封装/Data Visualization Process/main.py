@@ -15,6 +15,7 @@ LIMS数据处理程序 - 电池数据分析工具
 """
 
 import os
+import random
 import sys
 from typing import Optional
 
@@ -125,6 +126,19 @@ def interactive_mode() -> Optional[list]:
     return args
 
 
+def apply_seed(seed: Optional[int]):
+    """固定 random 和 NumPy 的随机数种子
+
+    Args:
+        seed: 随机数种子，为None时不固定
+    """
+    if seed is None:
+        return
+    import numpy as np
+    random.seed(seed)
+    np.random.seed(seed)
+
+
 def main():
     """主程序入口"""
     try:
@@ -141,6 +155,7 @@ def main():
         # 解析配置
         config_parser = ConfigParser()
         config = config_parser.parse_arguments(args)
+        apply_seed(config.seed)
 
         print(f"\n配置信息:")
         print(f"  输入文件夹: {config.input_folder}")
@@ -4,10 +4,10 @@
 主程序处理器 - 集成所有模块的完整数据处理流程
 """
 
+import json
 import os
 import sys
 import time
-import glob
 from typing import Dict, List, Optional, Any
 import pandas as pd
 from tqdm import tqdm
@@ -33,7 +33,8 @@ class MainProcessor:
             config: 配置对象
         """
         self.config = config
-        self.logger = ProcessingLogger(config.input_folder)
+        # 日志写入输出文件夹（未指定时即输入文件夹），不与原始数据混在一起
+        self.logger = ProcessingLogger(config.output_folder, config.log_level)
 
         # 初始化各个模块
         self.file_parser = FileParser(config, self.logger)
@@ -50,6 +51,7 @@ class MainProcessor:
         # 处理统计
         self.total_processed = 0
         self.total_successful = 0
+        self.outliers_removed = 0
         self.start_time = None
         
         self.logger.log_info("主程序处理器初始化完成")
@@ -99,6 +101,7 @@ class MainProcessor:
             # 8. 导出结果
             start_time_str = time.strftime('%H%M%S', time.localtime(self.start_time))
             self._export_results(start_time_str)
+            self._write_summary()
 
             # 9. 输出处理总结
             self._print_processing_summary()
@@ -128,7 +131,15 @@ class MainProcessor:
             return False
         
         # 检查是否有Excel文件
-        excel_files = glob.glob(os.path.join(self.config.input_folder, "*.xlsx"))
+        excel_files = self._input_files()
+        if self.config.files:
+            missing = [path for path in excel_files if not os.path.isfile(path)]
+            for path in missing:
+                self.logger.log_warning(f"指定的文件不存在: {path}")
+            excel_files = [path for path in excel_files if path not in missing]
+            for path in self.config.files:
+                if not path.lower().endswith('.xlsx'):
+                    self.logger.log_warning(f"不支持的文件类型，只处理.xlsx文件: {path}")
         if not excel_files:
             self.logger.log_warning(f"输入文件夹中没有Excel文件: {self.config.input_folder}")
             self.logger.log_info("将只生成空的汇总表")
@@ -137,6 +148,20 @@ class MainProcessor:
         
         return True
     
+    def _input_files(self) -> List[str]:
+        """获取要处理的文件
+
+        Returns:
+            List[str]: 指定了 --files 时为其中的.xlsx文件，否则为输入文件夹中的所有.xlsx文件（扩展名不区分大小写）
+        """
+        if self.config.files:
+            return [path for path in self.config.files if path.lower().endswith('.xlsx')]
+        folder = self.config.input_folder
+        return [
+            os.path.join(folder, name) for name in sorted(os.listdir(folder))
+            if name.lower().endswith('.xlsx') and not name.startswith('.')
+        ]
+
     def _discover_and_group_files(self) -> Dict[str, List[str]]:
         """发现和分组文件
         
@@ -146,7 +171,7 @@ class MainProcessor:
         self.logger.log_info("开始文件发现和分组...")
         
         # 获取所有Excel文件
-        excel_files = glob.glob(os.path.join(self.config.input_folder, "*.xlsx"))
+        excel_files = [path for path in self._input_files() if os.path.isfile(path)]
         
         # 按系列分组
         file_groups = {}
@@ -178,6 +203,7 @@ class MainProcessor:
         self.logger.log_info("开始处理所有文件...")
         
         all_results = []
+        total_files = sum(len(files) for files in file_groups.values())
         
         for series_name, files in file_groups.items():
             if not files:
@@ -202,6 +228,10 @@ class MainProcessor:
                         
                 except Exception as e:
                     self.logger.log_warning(f"处理文件失败: {os.path.basename(file_path)}, 错误: {str(e)}")
+
+                # 桌面端按 "PROGRESS 已处理/总数" 格式解析进度
+                if self.config.enable_progress_bar:
+                    print(f"PROGRESS {self.total_processed}/{total_files}", flush=True)
             
             self.logger.log_info(f"系列 {series_name} 处理完成: {series_successful}/{len(files)} 个文件成功")
             
@@ -403,6 +433,7 @@ class MainProcessor:
             if cleaned_data is not None:
                 removed_count = original_count - len(cleaned_data)
                 self.all_cycle_data = cleaned_data
+                self.outliers_removed += removed_count
 
                 self.logger.log_info(f"异常检测完成: 原始数据 {original_count} 条，移除 {removed_count} 条，剩余 {len(cleaned_data)} 条")
             else:
@@ -477,10 +508,15 @@ class MainProcessor:
         """
         self.logger.log_info("开始导出结果...")
 
+        if self.config.output_stdout:
+            self._write_results_to_stdout()
+            return
+
         try:
-            # 生成输出文件名
-            output_filename = f"电池数据汇总表-{start_time_str}.xlsx"
-            output_path = os.path.join(self.config.input_folder, output_filename)
+            # 生成输出文件名，结果写入输出文件夹（未指定时即输入文件夹）
+            output_filename = f"{self._output_name(start_time_str)}.xlsx"
+            os.makedirs(self.config.output_folder, exist_ok=True)
+            output_path = os.path.join(self.config.output_folder, output_filename)
 
             # 使用ExcelWriter写入多个工作表
             with pd.ExcelWriter(output_path, engine='openpyxl') as writer:
@@ -513,6 +549,78 @@ class MainProcessor:
         except Exception as e:
             self.logger.log_error(f"结果导出过程中发生错误: {str(e)}")
 
+    def _write_summary(self):
+        """在输出文件夹中写入运行摘要 summary.json
+
+        字段名与桌面端 RunSummary 一致，无法计算的数值写为 null；结果输出到stdout时不写入
+        """
+        if self.config.output_stdout:
+            return
+
+        def column_mean(column: str) -> Optional[float]:
+            if column not in self.all_cycle_data.columns:
+                return None
+            mean = pd.to_numeric(self.all_cycle_data[column], errors='coerce').mean()
+            return None if pd.isna(mean) else float(mean)
+
+        summary = {
+            'files_processed': self.total_processed,
+            'files_failed': self.total_processed - self.total_successful,
+            'outliers_removed': self.outliers_removed,
+            'mean_efficiency': column_mean('首效'),
+            'mean_discharge_capacity': column_mean('首放'),
+            'outlier_method': self.config.outlier_method,
+            'reference_channel': self.config.reference_channel_method,
+        }
+        try:
+            os.makedirs(self.config.output_folder, exist_ok=True)
+            summary_path = os.path.join(self.config.output_folder, 'summary.json')
+            with open(summary_path, 'w', encoding='utf-8') as f:
+                json.dump(summary, f, ensure_ascii=False, indent=2)
+            self.logger.log_info(f"运行摘要已写入: {summary_path}")
+        except Exception as e:
+            self.logger.log_error(f"写入运行摘要失败: {str(e)}")
+
+    def _output_name(self, start_time_str: str) -> str:
+        """生成结果文件名（不含扩展名）
+
+        Args:
+            start_time_str: 开始时间字符串
+
+        Returns:
+            str: 按 --output_name_template 替换占位符后的文件名，未指定模板时为默认文件名
+        """
+        template = self.config.output_name_template
+        if not template:
+            return f"电池数据汇总表-{start_time_str}"
+
+        input_name = os.path.basename(os.path.normpath(os.path.abspath(self.config.input_folder)))
+        tokens = {
+            'date': time.strftime('%Y%m%d', time.localtime(self.start_time)),
+            'time': start_time_str,
+            'input_name': input_name,
+            'method': self.config.outlier_method,
+        }
+        name = template
+        for token, value in tokens.items():
+            name = name.replace(f"{{{token}}}", value)
+        return name
+
+    def _write_results_to_stdout(self):
+        """将主数据表以CSV格式输出到stdout
+
+        每一物理行（包括引号字段内换行后的续行）都加 "RESULT_DATA " 前缀，与日志行区分；
+        只按换行符拆分，桌面端拼接后按CSV解析。直接写入原始stdout，不进入主日志文件
+        """
+        stdout = self.logger.original_stdout
+        if not self.all_cycle_data.empty:
+            csv_text = self.all_cycle_data.to_csv(index=False)
+            for line in csv_text.rstrip('\r\n').split('\n'):
+                line = line.rstrip('\r')
+                stdout.write(f"RESULT_DATA {line}\n")
+        stdout.flush()
+        self.logger.log_info(f"结果已输出到stdout: {len(self.all_cycle_data)} 条记录")
+
     def _print_processing_summary(self):
         """输出处理总结"""
         end_time = time.time()
@@ -6,9 +6,13 @@
 """
 
 import argparse
+import json
 import os
 from typing import Dict, Any, List, Tuple, Optional
 
+# 分析脚本版本，main.py --version 输出
+VERSION = '2.0'
+
 
 class ConfigParser:
     """配置解析器类"""
@@ -33,12 +37,23 @@ class ConfigParser:
             """
         )
         
+        parser.add_argument(
+            '--version',
+            action='version',
+            version=f'%(prog)s {VERSION}',
+            help='显示版本号并退出'
+        )
+
         # ===== 基础运行参数 =====
         basic_group = parser.add_argument_group('基础运行参数')
+        basic_group.add_argument(
+            '--config',
+            help='JSON配置文件路径（键与参数名一致，命令行参数优先）'
+        )
         basic_group.add_argument(
             '--input_folder', 
             required=True, 
-            help='输入文件夹路径'
+            help='输入文件夹路径（使用 --config 时可在配置文件中指定）'
         )
         basic_group.add_argument(
             '--output_folder', 
@@ -56,12 +71,29 @@ class ConfigParser:
             default='retention_curve_mse',
             help='参考通道选择方法 (默认: retention_curve_mse)'
         )
+        basic_group.add_argument(
+            '--output_name_template',
+            help='结果文件名模板（不含扩展名），可用占位符 {date} {time} {input_name} {method}（可选）'
+        )
+        basic_group.add_argument(
+            '--files',
+            nargs='+',
+            default=[],
+            help='只处理指定的数据文件（可选，默认处理输入文件夹中的所有Excel文件）'
+        )
         basic_group.add_argument(
             '--output_format', 
             choices=['xlsx', 'csv'], 
             default='xlsx',
             help='输出格式 (默认: xlsx)'
         )
+        basic_group.add_argument(
+            '--output-stdout',
+            dest='output_stdout',
+            action='store_true',
+            default=False,
+            help='不写入结果文件，将主数据表以CSV格式逐行输出到stdout，每行前缀 "RESULT_DATA " (默认: False)'
+        )
         
         # ===== Excel读取配置 =====
         excel_group = parser.add_argument_group('Excel读取配置')
@@ -148,6 +180,13 @@ class ConfigParser:
         self._add_mode_config_params(self.parser)
         self._add_series_config_params(self.parser)
 
+        # 预先读取 --config，配置文件中的值作为参数默认值
+        pre_parser = argparse.ArgumentParser(add_help=False)
+        pre_parser.add_argument('--config')
+        pre_args, _ = pre_parser.parse_known_args(args)
+        if pre_args.config:
+            self._apply_config_file(pre_args.config)
+
         # 解析参数
         parsed_args = self.parser.parse_args(args)
 
@@ -158,6 +197,33 @@ class ConfigParser:
         self._validate_config(config)
 
         return config
+
+    def _apply_config_file(self, path: str):
+        """读取JSON配置文件，将其中的值设为参数默认值
+
+        只使用与参数名一致的键，其他键（如桌面端自身的设置）忽略；
+        值为 null 或空字符串的键视为未设置。
+
+        Args:
+            path: JSON配置文件路径
+        """
+        try:
+            with open(path, 'r', encoding='utf-8') as f:
+                values = json.load(f)
+        except (OSError, ValueError) as e:
+            self.parser.error(f"无法读取配置文件 {path}: {e}")
+        if not isinstance(values, dict):
+            self.parser.error(f"配置文件格式错误，应为JSON对象: {path}")
+
+        actions = {action.dest: action for action in self.parser._actions}
+        defaults = {
+            key: value for key, value in values.items()
+            if key in actions and key != 'config' and value is not None and value != ''
+        }
+        self.parser.set_defaults(**defaults)
+        # 配置文件已提供的必填参数不再要求在命令行中给出
+        for key in defaults:
+            actions[key].required = False
     
     def _validate_config(self, config: 'Config'):
         """验证配置参数的有效性
@@ -189,6 +255,9 @@ class ConfigParser:
         if config.discharge_diff_threshold < 0:
             raise ValueError("discharge_diff_threshold 必须大于等于 0")
 
+        if config.seed is not None and not (0 <= config.seed <= 2**32 - 1):
+            raise ValueError("seed 必须在 0 到 2^32-1 之间")
+
     def _add_reference_channel_params(self, parser: argparse.ArgumentParser):
         """添加参考通道选择配置参数"""
         ref_group = parser.add_argument_group('参考通道选择配置')
@@ -261,6 +330,30 @@ class ConfigParser:
             default=False,
             help='是否显示详细输出 (默认: False)'
         )
+        runtime_group.add_argument(
+            '--enable_progress_bar',
+            action='store_true',
+            default=False,
+            help='输出 "PROGRESS 已处理/总数" 进度行，供桌面端显示进度 (默认: False)'
+        )
+        runtime_group.add_argument(
+            '--log_level',
+            choices=['DEBUG', 'INFO', 'WARNING', 'ERROR'],
+            default='INFO',
+            help='控制台日志级别，低于该级别的信息只写入日志文件 (默认: INFO)'
+        )
+        runtime_group.add_argument(
+            '--deterministic',
+            action='store_true',
+            default=False,
+            help='固定随机数种子（未指定 --seed 时为 0），相同输入总是得到相同结果 (默认: False)'
+        )
+        runtime_group.add_argument(
+            '--seed',
+            type=int,
+            default=None,
+            help='random 和 NumPy 的随机数种子，范围 0 到 2^32-1 (默认: 不固定)'
+        )
 
     def _add_data_validation_params(self, parser: argparse.ArgumentParser):
         """添加数据验证配置参数"""
@@ -445,6 +538,9 @@ class Config:
         self.outlier_method = args.outlier_method
         self.reference_channel_method = args.reference_channel_method
         self.output_format = args.output_format
+        self.output_stdout = getattr(args, 'output_stdout', False)
+        self.files = getattr(args, 'files', None) or []
+        self.output_name_template = getattr(args, 'output_name_template', None)
         
         # Excel读取配置
         self.excel_engine = args.excel_engine
@@ -528,6 +624,10 @@ class Config:
         self.auto_open_results = getattr(args, 'auto_open_results', False)
         self.backup_original_data = getattr(args, 'backup_original_data', True)
         self.log_level = getattr(args, 'log_level', 'INFO')
+        self.deterministic = getattr(args, 'deterministic', False)
+        self.seed = getattr(args, 'seed', None)
+        if self.seed is None and self.deterministic:
+            self.seed = 0
 
         # 数据验证配置
         self.min_cycles_required = getattr(args, 'min_cycles_required', 2)
@@ -106,12 +106,12 @@ class FileParser:
         Returns:
             List[str]: Excel文件路径列表
         """
-        excel_patterns = ['*.xlsx', '*.xls']
-        excel_files = []
-        
-        for pattern in excel_patterns:
-            files = glob.glob(os.path.join(folder_path, pattern))
-            excel_files.extend(files)
+        # 扩展名不区分大小写（仪器导出的文件可能为 REPORT.XLSX）
+        excel_extensions = ('.xlsx', '.xls')
+        excel_files = [
+            os.path.join(folder_path, name) for name in sorted(os.listdir(folder_path))
+            if name.lower().endswith(excel_extensions)
+        ]
         
         # 过滤掉临时文件和备份文件
         filtered_files = []
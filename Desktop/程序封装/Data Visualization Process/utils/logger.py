@@ -38,6 +38,10 @@ class TeeOutput:
             file.flush()
 
 
+# 控制台日志级别，数值越大越严重
+LOG_LEVELS = {'DEBUG': 10, 'INFO': 20, 'WARNING': 30, 'ERROR': 40}
+
+
 class ProcessingLogger:
     """处理日志管理器
     
@@ -47,13 +51,15 @@ class ProcessingLogger:
     - 调试详细日志
     """
     
-    def __init__(self, output_dir: str = None):
+    def __init__(self, output_dir: str = None, log_level: str = 'INFO'):
         """初始化日志管理器
         
         Args:
             output_dir: 输出目录，如果为None则使用当前工作目录
+            log_level: 控制台日志级别，低于该级别的信息只写入主日志文件
         """
         self.output_dir = output_dir or os.getcwd()
+        self.log_level = LOG_LEVELS.get(str(log_level).upper(), LOG_LEVELS['INFO'])
         self.timestamp = time.strftime('%Y%m%d_%H%M%S')
 
         # 创建日志文件夹
@@ -108,6 +114,21 @@ class ProcessingLogger:
         timestamp = time.strftime('%H:%M:%S')
         self.debug_log_file.write(f"[{timestamp}] {message}\n")
         self.debug_log_file.flush()
+        if self.log_level <= LOG_LEVELS['DEBUG']:
+            self.original_stdout.write(f"调试: {message}\n")
+            self.original_stdout.flush()
+
+    def _emit(self, level: str, message: str):
+        """按控制台日志级别输出，级别不足时只写入主日志文件
+
+        Args:
+            level: 消息级别
+            message: 要输出的消息
+        """
+        if LOG_LEVELS[level] >= self.log_level:
+            print(message)
+        else:
+            self.main_log_file.write(f"{message}\n")
 
     def log_info(self, message: str):
         """记录一般信息（输出到主日志）
@@ -115,7 +136,7 @@ class ProcessingLogger:
         Args:
             message: 要记录的信息
         """
-        print(message)
+        self._emit('INFO', message)
 
     def log_warning(self, message: str):
         """记录警告信息
@@ -124,7 +145,7 @@ class ProcessingLogger:
             message: 警告消息
         """
         warning_msg = f"警告: {message}"
-        print(warning_msg)
+        self._emit('WARNING', warning_msg)
         self.log_debug(f"WARNING: {message}")
 
     def log_error(self, message: str):
@@ -134,7 +155,7 @@ class ProcessingLogger:
             message: 错误消息
         """
         error_msg = f"错误: {message}"
-        print(error_msg)
+        self._emit('ERROR', error_msg)
         self.log_debug(f"ERROR: {message}")
 
     def close(self):